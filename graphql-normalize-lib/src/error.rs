@@ -0,0 +1,154 @@
+use graphql_parser::query::ParseError;
+use std::fmt::{Display, Write};
+
+/// Errors returned while normalizing or hashing a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NormalizeError {
+    /// The query could not be parsed. `position` is the one-based line and
+    /// column, when the parser reported one.
+    Parse {
+        message: String,
+        position: Option<(usize, usize)>,
+    },
+    /// Fragments spread each other in a cycle, so they cannot be inlined.
+    /// `path` starts and ends with the same fragment and the position is that
+    /// of the spread closing the cycle.
+    FragmentCycle {
+        path: Vec<String>,
+        line: usize,
+        column: usize,
+    },
+    /// The normalized query could not be minified.
+    Minify(String),
+}
+
+impl NormalizeError {
+    /// The one-based line and column the error points at, if it has one.
+    pub fn position(&self) -> Option<(usize, usize)> {
+        match self {
+            NormalizeError::Parse { position, .. } => *position,
+            NormalizeError::FragmentCycle { line, column, .. } => Some((*line, *column)),
+            NormalizeError::Minify(_) => None,
+        }
+    }
+
+    /// Renders the error as a diagnostic with a code frame pointing into
+    /// `source`, the query text that produced it.
+    ///
+    /// ```text
+    /// error: Unexpected `}[Punctuator]`; Expected end of input
+    ///  --> 1:7
+    ///   |
+    /// 1 | { a } }
+    ///   |       ^
+    /// ```
+    pub fn render(&self, source: &str) -> String {
+        let mut out = String::new();
+        let _ = write!(out, "error: {}", self.message());
+
+        let Some((line, column)) = self.position() else {
+            return out;
+        };
+        let Some(text) = source.lines().nth(line.saturating_sub(1)) else {
+            let _ = write!(out, "\n --> {line}:{column}");
+            return out;
+        };
+
+        let gutter = " ".repeat(line.to_string().len());
+        let _ = write!(
+            out,
+            "\n{gutter}--> {line}:{column}\n{gutter} |\n{line} | {text}\n{gutter} | {}^",
+            " ".repeat(column.saturating_sub(1))
+        );
+        out
+    }
+
+    fn message(&self) -> String {
+        match self {
+            NormalizeError::Parse { message, .. } => message.clone(),
+            NormalizeError::FragmentCycle { path, .. } => {
+                format!("fragment cycle detected: {}", path.join(" -> "))
+            }
+            NormalizeError::Minify(message) => format!("minify error: {message}"),
+        }
+    }
+}
+
+impl Display for NormalizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NormalizeError::Parse {
+                message,
+                position: Some((line, column)),
+            } => write!(f, "parse error at {line}:{column}: {message}"),
+            NormalizeError::Parse {
+                message,
+                position: None,
+            } => write!(f, "parse error: {message}"),
+            NormalizeError::FragmentCycle { .. } | NormalizeError::Minify(_) => {
+                f.write_str(&self.message())
+            }
+        }
+    }
+}
+
+impl std::error::Error for NormalizeError {}
+
+impl From<ParseError> for NormalizeError {
+    /// graphql_parser keeps its error opaque, so the position is recovered
+    /// from the rendered `Parse error at <line>:<column>` header.
+    fn from(err: ParseError) -> Self {
+        let text = err.to_string();
+        let text = text.strip_prefix("query parse error: ").unwrap_or(&text);
+        let mut lines = text.lines();
+
+        let position = lines
+            .next()
+            .and_then(|header| header.strip_prefix("Parse error at "))
+            .and_then(|pos| pos.split_once(':'))
+            .and_then(|(line, column)| Some((line.parse().ok()?, column.parse().ok()?)));
+
+        let message = match position {
+            Some(_) => lines.collect::<Vec<_>>().join("; "),
+            None => text.trim().to_string(),
+        };
+        NormalizeError::Parse { message, position }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{normalize, NormalizeError};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_error_has_position() {
+        let err = normalize("{\n  user {\n    name(id: )\n  }\n}").unwrap_err();
+
+        assert_eq!(err.position(), Some((3, 14)));
+        assert!(matches!(err, NormalizeError::Parse { .. }));
+    }
+
+    #[test]
+    fn renders_message_without_position() {
+        let err = NormalizeError::Parse {
+            message: String::from("unexpected end of input"),
+            position: None,
+        };
+
+        assert_eq!(err.position(), None);
+        assert_eq!(err.render("{ a"), "error: unexpected end of input");
+        assert_eq!(err.to_string(), "parse error: unexpected end of input");
+    }
+
+    #[test]
+    fn renders_code_frame() {
+        let source = "{ a } }";
+        let err = normalize(source).unwrap_err();
+
+        assert_eq!(
+            err.render(source),
+            "error: Unexpected `}[Punctuator]`; Expected end of input\n --> 1:7\n  |\n1 | { a } }\n  |       ^"
+        );
+    }
+}
@@ -1,13 +1,164 @@
+mod error;
+
+pub use error::NormalizeError;
+pub use graphql_printer::PrintOptions;
+
 use graphql_parser::query::{
-    self, Definition, Directive, Document, Selection, Value, VariableDefinition,
+    self, Definition, Directive, Document, FragmentDefinition, InlineFragment, Selection,
+    SelectionSet, TypeCondition, Value, VariableDefinition,
 };
-use std::fmt::Display;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Write};
+
+/// Options controlling [`normalize_with`].
+#[derive(Debug, Clone, Default)]
+pub struct NormalizeOptions {
+    /// Replace literal values with zero values and drop aliases, see [`signature`].
+    pub signature: bool,
+    /// Replace fragment spreads with the selections of the fragment they name.
+    pub inline_fragments: bool,
+    /// Rename variables to `$v0`, `$v1`, ... in order of first use within each
+    /// operation.
+    pub rename_variables: bool,
+    /// How the normalized document is printed.
+    pub print: PrintOptions,
+}
+
+/// The result of [`normalize_with`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Normalized {
+    pub document: String,
+    /// The renamed variables of each operation, in document order. Empty
+    /// unless variables were renamed.
+    pub variables: Vec<RenamedVariables>,
+}
+
+/// The variables renamed in a single operation, as returned in
+/// [`Normalized::variables`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenamedVariables {
+    pub operation: Option<String>,
+    /// Original and new variable names, without the leading `$`, in the order
+    /// they were assigned.
+    pub variables: Vec<(String, String)>,
+}
+
+impl Display for Normalized {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.document)
+    }
+}
+
+pub fn normalize(s: &str) -> Result<String, NormalizeError> {
+    normalize_with(s, &NormalizeOptions::default()).map(|n| n.document)
+}
+
+/// Normalizes the query into its operation signature: literal values are
+/// replaced with zero values and aliases are dropped, so operations that only
+/// differ by inlined values produce the same output.
+pub fn signature(s: &str) -> Result<String, NormalizeError> {
+    let options = NormalizeOptions {
+        signature: true,
+        ..Default::default()
+    };
+    normalize_with(s, &options).map(|n| n.document)
+}
+
+pub fn normalize_with(s: &str, options: &NormalizeOptions) -> Result<Normalized, NormalizeError> {
+    let document = query::parse_query::<String>(s)?;
+    let mut doc = Doc::new(document);
+    if options.inline_fragments {
+        doc.inline_fragments()?;
+    }
+    if options.signature {
+        doc.strip_literals();
+    }
+    doc.normalize();
+    let variables = if options.rename_variables {
+        doc.rename_variables()?
+    } else {
+        Vec::new()
+    };
+    Ok(Normalized {
+        document: graphql_printer::print_query(&doc.0, &options.print),
+        variables,
+    })
+}
 
-pub fn normalize(s: &str) -> Result<String, Box<dyn std::error::Error>> {
+/// Returns the hex encoded SHA-256 of the normalized, minified query. Since
+/// the hash is taken after normalization, reordering fields or arguments does
+/// not change it.
+pub fn hash(s: &str) -> Result<String, NormalizeError> {
     let document = query::parse_query::<String>(s)?;
     let mut doc = Doc::new(document);
     doc.normalize();
-    Ok(format!("{doc}"))
+    let minified = minify(format!("{doc}"))?;
+    Ok(sha256_hex(&minified))
+}
+
+/// The hash of a single operation, as returned by [`hash_operations`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationHash {
+    pub name: Option<String>,
+    pub hash: String,
+}
+
+/// Hashes every operation in the query separately, together with the fragments
+/// it uses. Results are in normalized operation order.
+pub fn hash_operations(s: &str) -> Result<Vec<OperationHash>, NormalizeError> {
+    let document = query::parse_query::<String>(s)?;
+    let mut doc = Doc::new(document);
+    doc.normalize();
+
+    let mut hashes = Vec::new();
+    for definition in &doc.0.definitions {
+        let Definition::Operation(op) = definition else {
+            continue;
+        };
+
+        let name = operation_name(op);
+        let used = doc.used_fragments(op);
+        let mut definitions = vec![definition.clone()];
+        for fragment in &doc.0.definitions {
+            if let Definition::Fragment(frag) = fragment {
+                if used.contains(frag.name.as_str()) {
+                    definitions.push(fragment.clone());
+                }
+            }
+        }
+
+        let operation_doc = Document { definitions };
+        let minified = minify(format!("{operation_doc}"))?;
+        hashes.push(OperationHash {
+            name,
+            hash: sha256_hex(&minified),
+        });
+    }
+
+    Ok(hashes)
+}
+
+fn operation_name(op: &query::OperationDefinition<String>) -> Option<String> {
+    match op {
+        query::OperationDefinition::SelectionSet(_) => None,
+        query::OperationDefinition::Query(q) => q.name.clone(),
+        query::OperationDefinition::Mutation(m) => m.name.clone(),
+        query::OperationDefinition::Subscription(s) => s.name.clone(),
+    }
+}
+
+fn minify(s: String) -> Result<String, NormalizeError> {
+    graphql_parser::minify_query(s).map_err(|e| NormalizeError::Minify(e.to_string()))
+}
+
+fn sha256_hex(s: &str) -> String {
+    Sha256::digest(s.as_bytes())
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
 }
 
 struct Doc<'a>(Document<'a, String>);
@@ -23,6 +174,376 @@ impl<'a> Doc<'a> {
         Self(document)
     }
 
+    /// Names of the fragments reachable from the operation, following spreads
+    /// inside fragments transitively.
+    fn used_fragments<'s>(
+        &'s self,
+        op: &'s query::OperationDefinition<'a, String>,
+    ) -> HashSet<&'s str> {
+        let selection_set = match op {
+            query::OperationDefinition::SelectionSet(set) => set,
+            query::OperationDefinition::Query(q) => &q.selection_set,
+            query::OperationDefinition::Mutation(m) => &m.selection_set,
+            query::OperationDefinition::Subscription(s) => &s.selection_set,
+        };
+
+        let mut used = HashSet::new();
+        let mut pending = Vec::new();
+        collect_fragment_spreads(&selection_set.items, &mut pending);
+
+        while let Some(name) = pending.pop() {
+            if !used.insert(name) {
+                continue;
+            }
+            for definition in &self.0.definitions {
+                if let Definition::Fragment(frag) = definition {
+                    if frag.name == name {
+                        collect_fragment_spreads(&frag.selection_set.items, &mut pending);
+                    }
+                }
+            }
+        }
+
+        used
+    }
+
+    /// Replaces fragment spreads with the selections of the fragments they name
+    /// and removes the fragment definitions that were inlined into operations.
+    fn inline_fragments(&mut self) -> Result<(), NormalizeError> {
+        let fragments: HashMap<String, FragmentDefinition<'a, String>> = self
+            .0
+            .definitions
+            .iter()
+            .filter_map(|d| match d {
+                Definition::Fragment(frag) => Some((frag.name.clone(), frag.clone())),
+                Definition::Operation(_) => None,
+            })
+            .collect();
+
+        let mut inlined = HashSet::new();
+        for definition in &mut self.0.definitions {
+            if let Definition::Operation(op) = definition {
+                let selection_set = match op {
+                    query::OperationDefinition::SelectionSet(set) => set,
+                    query::OperationDefinition::Query(q) => &mut q.selection_set,
+                    query::OperationDefinition::Mutation(m) => &mut m.selection_set,
+                    query::OperationDefinition::Subscription(s) => &mut s.selection_set,
+                };
+                inline_selection_set(
+                    &mut selection_set.items,
+                    None,
+                    &fragments,
+                    &mut Vec::new(),
+                    &mut inlined,
+                )?;
+            }
+        }
+
+        // Fragments never spread by an operation are kept, with their own
+        // spreads inlined, so fragment-only documents still normalize.
+        for definition in &mut self.0.definitions {
+            if let Definition::Fragment(frag) = definition {
+                let TypeCondition::On(type_condition) = &frag.type_condition;
+                inline_selection_set(
+                    &mut frag.selection_set.items,
+                    Some(type_condition),
+                    &fragments,
+                    &mut vec![frag.name.clone()],
+                    &mut HashSet::new(),
+                )?;
+            }
+        }
+
+        self.0.definitions.retain(|d| match d {
+            Definition::Fragment(frag) => !inlined.contains(&frag.name),
+            Definition::Operation(_) => true,
+        });
+
+        Ok(())
+    }
+
+    /// Renames variables to `$v0`, `$v1`, ... in order of first use within
+    /// each operation, following the fragments it spreads the first time they
+    /// are seen. Variables that are defined but never used are numbered after
+    /// the used ones. Fragments no operation spreads are numbered together, as
+    /// if they were one more operation.
+    ///
+    /// A fragment's variables belong to every operation spreading it, so
+    /// fragments shared by operations that number their variables differently
+    /// are inlined into them first.
+    fn rename_variables(&mut self) -> Result<Vec<RenamedVariables>, NormalizeError> {
+        let numbering = loop {
+            let numbering = self.number_variables();
+            if numbering.conflicting.is_empty() {
+                break numbering;
+            }
+            self.inline_named_fragments(&numbering.conflicting)?;
+        };
+
+        let lookups: Vec<HashMap<&str, &str>> = numbering
+            .units
+            .iter()
+            .map(|renamed| {
+                renamed
+                    .iter()
+                    .map(|(old, new)| (old.as_str(), new.as_str()))
+                    .collect()
+            })
+            .collect();
+        let empty = HashMap::new();
+        let mut operation = 0;
+        for definition in &mut self.0.definitions {
+            let lookup = match definition {
+                Definition::Operation(_) => {
+                    operation += 1;
+                    &lookups[operation - 1]
+                }
+                Definition::Fragment(frag) => numbering
+                    .fragments
+                    .get(&frag.name)
+                    .map_or(&empty, |unit| &lookups[*unit]),
+            };
+
+            match definition {
+                Definition::Operation(op) => match op {
+                    query::OperationDefinition::SelectionSet(set) => {
+                        rename_selection_set(&mut set.items, lookup);
+                    }
+                    query::OperationDefinition::Query(query) => {
+                        rename_selection_set(&mut query.selection_set.items, lookup);
+                        rename_directives(&mut query.directives, lookup);
+                        rename_variable_definitions(&mut query.variable_definitions, lookup);
+                    }
+                    query::OperationDefinition::Mutation(mutation) => {
+                        rename_selection_set(&mut mutation.selection_set.items, lookup);
+                        rename_directives(&mut mutation.directives, lookup);
+                        rename_variable_definitions(&mut mutation.variable_definitions, lookup);
+                    }
+                    query::OperationDefinition::Subscription(subscription) => {
+                        rename_selection_set(&mut subscription.selection_set.items, lookup);
+                        rename_directives(&mut subscription.directives, lookup);
+                        rename_variable_definitions(&mut subscription.variable_definitions, lookup);
+                    }
+                },
+                Definition::Fragment(frag) => {
+                    rename_selection_set(&mut frag.selection_set.items, lookup);
+                    rename_directives(&mut frag.directives, lookup);
+                }
+            }
+        }
+
+        let names = self.0.definitions.iter().filter_map(|d| match d {
+            Definition::Operation(op) => Some(operation_name(op)),
+            Definition::Fragment(_) => None,
+        });
+        Ok(names
+            .zip(numbering.units)
+            .map(|(operation, variables)| RenamedVariables {
+                operation,
+                variables,
+            })
+            .collect())
+    }
+
+    /// Numbers the variables of each operation, and of the fragments no
+    /// operation spreads, listing the shared fragments the operations number
+    /// differently.
+    fn number_variables(&self) -> Numbering {
+        let fragments: HashMap<&str, &FragmentDefinition<String>> = self
+            .0
+            .definitions
+            .iter()
+            .filter_map(|d| match d {
+                Definition::Fragment(frag) => Some((frag.name.as_str(), frag)),
+                Definition::Operation(_) => None,
+            })
+            .collect();
+
+        // The variables of each unit, with the fragments it reaches.
+        let mut units: Vec<(Vec<&str>, HashSet<&str>)> = Vec::new();
+        let mut spread = HashSet::new();
+        for definition in &self.0.definitions {
+            let Definition::Operation(op) = definition else {
+                continue;
+            };
+            let (directives, variable_definitions, selection_set) = match op {
+                query::OperationDefinition::SelectionSet(set) => (&[][..], &[][..], set),
+                query::OperationDefinition::Query(q) => (
+                    &q.directives[..],
+                    &q.variable_definitions[..],
+                    &q.selection_set,
+                ),
+                query::OperationDefinition::Mutation(m) => (
+                    &m.directives[..],
+                    &m.variable_definitions[..],
+                    &m.selection_set,
+                ),
+                query::OperationDefinition::Subscription(s) => (
+                    &s.directives[..],
+                    &s.variable_definitions[..],
+                    &s.selection_set,
+                ),
+            };
+            let mut order = Vec::new();
+            let mut visited = HashSet::new();
+            collect_directive_variables(directives, &mut order);
+            collect_variables(&selection_set.items, &fragments, &mut visited, &mut order);
+            for variable_definition in variable_definitions {
+                push_unique(&mut order, &variable_definition.name);
+            }
+            spread.extend(visited.iter().copied());
+            units.push((order, visited));
+        }
+
+        let mut order = Vec::new();
+        let mut visited = HashSet::new();
+        for definition in &self.0.definitions {
+            if let Definition::Fragment(frag) = definition {
+                if !spread.contains(frag.name.as_str()) && visited.insert(frag.name.as_str()) {
+                    collect_directive_variables(&frag.directives, &mut order);
+                    collect_variables(
+                        &frag.selection_set.items,
+                        &fragments,
+                        &mut visited,
+                        &mut order,
+                    );
+                }
+            }
+        }
+        units.push((order, visited));
+
+        let mut numbering = Numbering::default();
+        for (unit, (order, reached)) in units.into_iter().enumerate() {
+            let renamed: Vec<(String, String)> = order
+                .into_iter()
+                .enumerate()
+                .map(|(i, name)| (name.to_string(), format!("v{i}")))
+                .collect();
+
+            for definition in &self.0.definitions {
+                let Definition::Fragment(frag) = definition else {
+                    continue;
+                };
+                if !reached.contains(frag.name.as_str()) {
+                    continue;
+                }
+                let Some(assigned) = numbering.fragments.get(&frag.name) else {
+                    numbering.fragments.insert(frag.name.clone(), unit);
+                    continue;
+                };
+                let mut own = Vec::new();
+                collect_directive_variables(&frag.directives, &mut own);
+                collect_variables(
+                    &frag.selection_set.items,
+                    &HashMap::new(),
+                    &mut HashSet::new(),
+                    &mut own,
+                );
+                let new_name = |renamed: &[(String, String)], variable: &str| {
+                    renamed
+                        .iter()
+                        .find(|(old, _)| old == variable)
+                        .map(|(_, new)| new.clone())
+                };
+                if own
+                    .iter()
+                    .any(|v| new_name(&numbering.units[*assigned], v) != new_name(&renamed, v))
+                {
+                    numbering.conflicting.insert(frag.name.clone());
+                }
+            }
+
+            numbering.units.push(renamed);
+        }
+
+        numbering
+    }
+
+    /// Replaces the spreads of the named fragments with their selections, in
+    /// operations and fragments alike, and removes their definitions.
+    fn inline_named_fragments(&mut self, names: &HashSet<String>) -> Result<(), NormalizeError> {
+        let fragments: HashMap<String, FragmentDefinition<'a, String>> = self
+            .0
+            .definitions
+            .iter()
+            .filter_map(|d| match d {
+                Definition::Fragment(frag) if names.contains(&frag.name) => {
+                    Some((frag.name.clone(), frag.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        self.0.definitions.retain(|d| match d {
+            Definition::Fragment(frag) => !names.contains(&frag.name),
+            Definition::Operation(_) => true,
+        });
+
+        for definition in &mut self.0.definitions {
+            match definition {
+                Definition::Operation(op) => {
+                    let selection_set = match op {
+                        query::OperationDefinition::SelectionSet(set) => set,
+                        query::OperationDefinition::Query(q) => &mut q.selection_set,
+                        query::OperationDefinition::Mutation(m) => &mut m.selection_set,
+                        query::OperationDefinition::Subscription(s) => &mut s.selection_set,
+                    };
+                    inline_selection_set(
+                        &mut selection_set.items,
+                        None,
+                        &fragments,
+                        &mut Vec::new(),
+                        &mut HashSet::new(),
+                    )?;
+                }
+                Definition::Fragment(frag) => {
+                    let TypeCondition::On(type_condition) = &frag.type_condition;
+                    inline_selection_set(
+                        &mut frag.selection_set.items,
+                        Some(type_condition),
+                        &fragments,
+                        &mut vec![frag.name.clone()],
+                        &mut HashSet::new(),
+                    )?;
+                }
+            }
+        }
+
+        // Inlined selections are normalized like the rest of the document.
+        self.normalize();
+        Ok(())
+    }
+
+    fn strip_literals(&mut self) {
+        for definition in &mut self.0.definitions {
+            match definition {
+                Definition::Operation(op) => match op {
+                    query::OperationDefinition::SelectionSet(set) => {
+                        strip_selection_set(&mut set.items);
+                    }
+                    query::OperationDefinition::Query(query) => {
+                        strip_selection_set(&mut query.selection_set.items);
+                        strip_directives(&mut query.directives);
+                        strip_variable_definitions(&mut query.variable_definitions);
+                    }
+                    query::OperationDefinition::Mutation(mutation) => {
+                        strip_selection_set(&mut mutation.selection_set.items);
+                        strip_directives(&mut mutation.directives);
+                        strip_variable_definitions(&mut mutation.variable_definitions);
+                    }
+                    query::OperationDefinition::Subscription(subscription) => {
+                        strip_selection_set(&mut subscription.selection_set.items);
+                        strip_directives(&mut subscription.directives);
+                        strip_variable_definitions(&mut subscription.variable_definitions);
+                    }
+                },
+                Definition::Fragment(frag) => {
+                    strip_selection_set(&mut frag.selection_set.items);
+                    strip_directives(&mut frag.directives);
+                }
+            }
+        }
+    }
+
     fn normalize(&mut self) {
         for definition in &mut self.0.definitions {
             match definition {
@@ -90,46 +611,123 @@ impl<'a> Doc<'a> {
     }
 }
 
-fn normalize_selection_set(selections: &mut [Selection<String>]) {
+fn normalize_selection_set(selections: &mut Vec<Selection<String>>) {
     for selection in selections.iter_mut() {
         match selection {
             Selection::Field(field) => {
                 normalize_directives(&mut field.directives);
-                normalize_selection_set(&mut field.selection_set.items);
                 field.arguments.sort_by_key(|(k, _v)| k.to_lowercase());
             }
             Selection::FragmentSpread(frag_spread) => {
                 normalize_directives(&mut frag_spread.directives);
             }
             Selection::InlineFragment(inline) => {
-                normalize_selection_set(&mut inline.selection_set.items);
                 normalize_directives(&mut inline.directives);
             }
         }
     }
 
-    selections.sort_by_key(|s| {
-        match s {
-            Selection::Field(f) => f.name.clone(),
-            Selection::FragmentSpread(fs) => {
-                let mut s = String::from("ZZZZ");
-                s += &fs.fragment_name;
-                s
+    // Merge before descending so the combined sub-selections are normalized,
+    // and merged, together.
+    merge_selections(selections);
+
+    for selection in selections.iter_mut() {
+        match selection {
+            Selection::Field(field) => normalize_selection_set(&mut field.selection_set.items),
+            Selection::FragmentSpread(_) => (),
+            Selection::InlineFragment(inline) => {
+                normalize_selection_set(&mut inline.selection_set.items)
             }
-            Selection::InlineFragment(f) => {
-                let mut s = String::from("ZZZZZZZZ");
-                if let Some(tc) = &f.type_condition {
-                    match tc {
-                        query::TypeCondition::On(on) => s += on,
-                    }
-                }
-                s
+        }
+    }
+
+    // Fields are ordered by response key, then by name and arguments, so
+    // aliased fields sharing a name do not depend on their input order.
+    selections.sort_by_cached_key(|s| match s {
+        Selection::Field(f) => (
+            f.alias.as_ref().unwrap_or(&f.name).to_lowercase(),
+            f.name.clone(),
+            f.arguments
+                .iter()
+                .map(|(k, v)| format!("{k}: {v}"))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        Selection::FragmentSpread(fs) => (
+            format!("ZZZZ{}", fs.fragment_name).to_lowercase(),
+            String::new(),
+            String::new(),
+        ),
+        Selection::InlineFragment(f) => {
+            let mut s = String::from("ZZZZZZZZ");
+            if let Some(query::TypeCondition::On(on)) = &f.type_condition {
+                s += on;
             }
+            (s.to_lowercase(), String::new(), String::new())
         }
-        .to_lowercase()
     });
 }
 
+/// Merges selections that are equivalent under the GraphQL field merging rules:
+/// fields with the same response key, name, arguments and directives have
+/// their sub-selections combined, as do inline fragments with the same type
+/// condition and directives. Repeated fragment spreads are dropped.
+///
+/// Arguments and directives are expected to be normalized already, so that
+/// equivalent lists compare equal.
+fn merge_selections(selections: &mut Vec<Selection<String>>) {
+    let mut merged: Vec<Selection<String>> = Vec::with_capacity(selections.len());
+
+    for selection in selections.drain(..) {
+        let existing = merged.iter_mut().find(|m| can_merge(m, &selection));
+
+        match (existing, selection) {
+            (Some(Selection::Field(existing)), Selection::Field(field)) => {
+                existing
+                    .selection_set
+                    .items
+                    .extend(field.selection_set.items);
+            }
+            (Some(Selection::InlineFragment(existing)), Selection::InlineFragment(inline)) => {
+                existing
+                    .selection_set
+                    .items
+                    .extend(inline.selection_set.items);
+            }
+            (Some(_), _) => (),
+            (None, selection) => merged.push(selection),
+        }
+    }
+
+    *selections = merged;
+}
+
+fn can_merge<'a>(a: &Selection<'a, String>, b: &Selection<'a, String>) -> bool {
+    match (a, b) {
+        (Selection::Field(a), Selection::Field(b)) => {
+            a.alias.as_ref().unwrap_or(&a.name) == b.alias.as_ref().unwrap_or(&b.name)
+                && a.name == b.name
+                && a.arguments == b.arguments
+                && same_directives(&a.directives, &b.directives)
+        }
+        (Selection::InlineFragment(a), Selection::InlineFragment(b)) => {
+            a.type_condition == b.type_condition && same_directives(&a.directives, &b.directives)
+        }
+        (Selection::FragmentSpread(a), Selection::FragmentSpread(b)) => {
+            a.fragment_name == b.fragment_name && same_directives(&a.directives, &b.directives)
+        }
+        _ => false,
+    }
+}
+
+/// Compares directives ignoring their source positions.
+fn same_directives<'a>(a: &[Directive<'a, String>], b: &[Directive<'a, String>]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|(a, b)| a.name == b.name && a.arguments == b.arguments)
+}
+
 fn normalize_directives(directives: &mut [Directive<String>]) {
     for directive in directives.iter_mut() {
         for (_argument, value) in directive.arguments.iter_mut() {
@@ -188,3 +786,631 @@ fn normalize_value(value: &mut Value<String>) {
         }
     }
 }
+
+fn collect_fragment_spreads<'s>(selections: &'s [Selection<String>], names: &mut Vec<&'s str>) {
+    for selection in selections {
+        match selection {
+            Selection::Field(field) => {
+                collect_fragment_spreads(&field.selection_set.items, names);
+            }
+            Selection::FragmentSpread(frag_spread) => names.push(&frag_spread.fragment_name),
+            Selection::InlineFragment(inline) => {
+                collect_fragment_spreads(&inline.selection_set.items, names);
+            }
+        }
+    }
+}
+
+/// Inlines the fragment spreads in `selections`. `parent_type` is the type
+/// condition of the closest enclosing fragment, if it is known. Spreads of a
+/// fragment on that same type are replaced by the fragment's selections, any
+/// other spread becomes an inline fragment so its type condition is preserved.
+/// `path` holds the fragments currently being inlined and is used to detect
+/// cycles.
+fn inline_selection_set<'a>(
+    selections: &mut Vec<Selection<'a, String>>,
+    parent_type: Option<&str>,
+    fragments: &HashMap<String, FragmentDefinition<'a, String>>,
+    path: &mut Vec<String>,
+    inlined: &mut HashSet<String>,
+) -> Result<(), NormalizeError> {
+    let mut result = Vec::with_capacity(selections.len());
+
+    for selection in selections.drain(..) {
+        match selection {
+            Selection::Field(mut field) => {
+                inline_selection_set(
+                    &mut field.selection_set.items,
+                    None,
+                    fragments,
+                    path,
+                    inlined,
+                )?;
+                result.push(Selection::Field(field));
+            }
+            Selection::InlineFragment(mut inline) => {
+                let type_condition = match &inline.type_condition {
+                    Some(TypeCondition::On(on)) => Some(on.as_str()),
+                    None => parent_type,
+                };
+                inline_selection_set(
+                    &mut inline.selection_set.items,
+                    type_condition,
+                    fragments,
+                    path,
+                    inlined,
+                )?;
+                result.push(Selection::InlineFragment(inline));
+            }
+            Selection::FragmentSpread(spread) => {
+                let Some(frag) = fragments.get(&spread.fragment_name) else {
+                    result.push(Selection::FragmentSpread(spread));
+                    continue;
+                };
+
+                if let Some(start) = path.iter().position(|name| *name == frag.name) {
+                    let mut cycle = path[start..].to_vec();
+                    cycle.push(frag.name.clone());
+                    return Err(NormalizeError::FragmentCycle {
+                        path: cycle,
+                        line: spread.position.line,
+                        column: spread.position.column,
+                    });
+                }
+
+                let TypeCondition::On(type_condition) = &frag.type_condition;
+                let mut items = frag.selection_set.items.clone();
+                path.push(frag.name.clone());
+                inline_selection_set(&mut items, Some(type_condition), fragments, path, inlined)?;
+                path.pop();
+                inlined.insert(frag.name.clone());
+
+                if parent_type == Some(type_condition.as_str()) && spread.directives.is_empty() {
+                    result.extend(items);
+                } else {
+                    result.push(Selection::InlineFragment(InlineFragment {
+                        position: spread.position,
+                        type_condition: Some(frag.type_condition.clone()),
+                        directives: spread.directives,
+                        selection_set: SelectionSet {
+                            span: frag.selection_set.span,
+                            items,
+                        },
+                    }));
+                }
+            }
+        }
+    }
+
+    *selections = result;
+    Ok(())
+}
+
+/// Variable numbering computed by [`Doc::number_variables`].
+#[derive(Default)]
+struct Numbering {
+    /// Original and new variable names of each operation, followed by those of
+    /// the fragments no operation spreads.
+    units: Vec<Vec<(String, String)>>,
+    /// The unit whose numbering each fragment uses.
+    fragments: HashMap<String, usize>,
+    /// Fragments reached from units that number their variables differently.
+    conflicting: HashSet<String>,
+}
+
+fn push_unique<'s>(names: &mut Vec<&'s str>, name: &'s str) {
+    if !names.contains(&name) {
+        names.push(name);
+    }
+}
+
+fn collect_variables<'s>(
+    selections: &'s [Selection<String>],
+    fragments: &HashMap<&str, &'s FragmentDefinition<String>>,
+    visited: &mut HashSet<&'s str>,
+    names: &mut Vec<&'s str>,
+) {
+    for selection in selections {
+        match selection {
+            Selection::Field(field) => {
+                for (_argument, value) in &field.arguments {
+                    collect_value_variables(value, names);
+                }
+                collect_directive_variables(&field.directives, names);
+                collect_variables(&field.selection_set.items, fragments, visited, names);
+            }
+            Selection::FragmentSpread(frag_spread) => {
+                collect_directive_variables(&frag_spread.directives, names);
+                if let Some(frag) = fragments.get(frag_spread.fragment_name.as_str()) {
+                    if visited.insert(&frag.name) {
+                        collect_directive_variables(&frag.directives, names);
+                        collect_variables(&frag.selection_set.items, fragments, visited, names);
+                    }
+                }
+            }
+            Selection::InlineFragment(inline) => {
+                collect_directive_variables(&inline.directives, names);
+                collect_variables(&inline.selection_set.items, fragments, visited, names);
+            }
+        }
+    }
+}
+
+fn collect_directive_variables<'s>(directives: &'s [Directive<String>], names: &mut Vec<&'s str>) {
+    for directive in directives {
+        for (_argument, value) in &directive.arguments {
+            collect_value_variables(value, names);
+        }
+    }
+}
+
+fn collect_value_variables<'s>(value: &'s Value<String>, names: &mut Vec<&'s str>) {
+    match value {
+        Value::Variable(name) => push_unique(names, name),
+        Value::List(list) => {
+            for value in list {
+                collect_value_variables(value, names);
+            }
+        }
+        Value::Object(object) => {
+            for value in object.values() {
+                collect_value_variables(value, names);
+            }
+        }
+        _ => (),
+    }
+}
+
+fn rename_selection_set(selections: &mut [Selection<String>], lookup: &HashMap<&str, &str>) {
+    for selection in selections.iter_mut() {
+        match selection {
+            Selection::Field(field) => {
+                for (_argument, value) in field.arguments.iter_mut() {
+                    rename_value(value, lookup);
+                }
+                rename_directives(&mut field.directives, lookup);
+                rename_selection_set(&mut field.selection_set.items, lookup);
+            }
+            Selection::FragmentSpread(frag_spread) => {
+                rename_directives(&mut frag_spread.directives, lookup);
+            }
+            Selection::InlineFragment(inline) => {
+                rename_directives(&mut inline.directives, lookup);
+                rename_selection_set(&mut inline.selection_set.items, lookup);
+            }
+        }
+    }
+}
+
+fn rename_directives(directives: &mut [Directive<String>], lookup: &HashMap<&str, &str>) {
+    for directive in directives.iter_mut() {
+        for (_argument, value) in directive.arguments.iter_mut() {
+            rename_value(value, lookup);
+        }
+    }
+}
+
+/// Renames the variable definitions and orders them by their new names.
+fn rename_variable_definitions(
+    variable_definitions: &mut [VariableDefinition<String>],
+    lookup: &HashMap<&str, &str>,
+) {
+    for variable_definition in variable_definitions.iter_mut() {
+        if let Some(default_value) = &mut variable_definition.default_value {
+            rename_value(default_value, lookup);
+        }
+        if let Some(new) = lookup.get(variable_definition.name.as_str()) {
+            variable_definition.name = new.to_string();
+        }
+    }
+
+    variable_definitions.sort_by_key(|vd| {
+        vd.name
+            .strip_prefix('v')
+            .and_then(|i| i.parse::<usize>().ok())
+    });
+}
+
+fn rename_value(value: &mut Value<String>, lookup: &HashMap<&str, &str>) {
+    match value {
+        Value::Variable(name) => {
+            if let Some(new) = lookup.get(name.as_str()) {
+                *name = new.to_string();
+            }
+        }
+        Value::List(list) => {
+            for value in list.iter_mut() {
+                rename_value(value, lookup);
+            }
+        }
+        Value::Object(object) => {
+            for value in object.values_mut() {
+                rename_value(value, lookup);
+            }
+        }
+        _ => (),
+    }
+}
+
+fn strip_selection_set(selections: &mut [Selection<String>]) {
+    for selection in selections.iter_mut() {
+        match selection {
+            Selection::Field(field) => {
+                field.alias = None;
+                for (_argument, value) in field.arguments.iter_mut() {
+                    strip_value(value);
+                }
+                strip_directives(&mut field.directives);
+                strip_selection_set(&mut field.selection_set.items);
+            }
+            Selection::FragmentSpread(frag_spread) => {
+                strip_directives(&mut frag_spread.directives);
+            }
+            Selection::InlineFragment(inline) => {
+                strip_directives(&mut inline.directives);
+                strip_selection_set(&mut inline.selection_set.items);
+            }
+        }
+    }
+}
+
+fn strip_directives(directives: &mut [Directive<String>]) {
+    for directive in directives.iter_mut() {
+        for (_argument, value) in directive.arguments.iter_mut() {
+            strip_value(value);
+        }
+    }
+}
+
+fn strip_variable_definitions(variable_definitions: &mut [VariableDefinition<String>]) {
+    for variable_definition in variable_definitions.iter_mut() {
+        if let Some(default_value) = &mut variable_definition.default_value {
+            strip_value(default_value);
+        }
+    }
+}
+
+/// Replaces literal values with the zero value of their kind. Variables,
+/// booleans, enums and nulls are kept since they describe the operation's
+/// shape rather than the data sent with it.
+fn strip_value(value: &mut Value<String>) {
+    match value {
+        Value::Int(_) => *value = Value::Int(0.into()),
+        Value::Float(_) => *value = Value::Float(0.0),
+        Value::String(_) => *value = Value::String(String::new()),
+        Value::List(_) => *value = Value::List(Vec::new()),
+        Value::Object(_) => *value = Value::Object(Default::default()),
+        Value::Variable(_) | Value::Boolean(_) | Value::Null | Value::Enum(_) => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn signature_strips_literals() {
+        let a = signature(r#"{ user(id: "1", first: 10, ratio: 1.5) { name } }"#).unwrap();
+        let b = signature(r#"{ user(ratio: 2.5, first: 3, id: "2") { name } }"#).unwrap();
+
+        assert_eq!(a, b);
+        assert_eq!(
+            a,
+            "{\n  user(first: 0, id: \"\", ratio: 0) {\n    name\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn signature_strips_lists_and_objects() {
+        let result = signature(
+            r#"query Q($ids: [ID!] = ["1"]) { users(ids: $ids, filter: {name: "a"}, tags: ["x", "y"], active: true, role: ADMIN) @include(if: true) { id } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            result,
+            "query Q($ids: [ID!] = []) {\n  users(active: true, filter: {}, ids: $ids, role: ADMIN, tags: []) @include(if: true) {\n    id\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn hash_ignores_ordering() {
+        let a = hash("query Q($b: Int, $a: Int) { user { name id } }").unwrap();
+        let b = hash("query Q($a: Int, $b: Int) {\n  user {\n    id\n    name\n  }\n}").unwrap();
+
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 64);
+        assert_eq!(
+            hash("{ a: user(id: 1) { x } b: user(id: 2) { y } }").unwrap(),
+            hash("{ b: user(id: 2) { y } a: user(id: 1) { x } }").unwrap()
+        );
+        assert_eq!(
+            hash("{ user(id: 1) { x } user(id: 2) { x } }").unwrap(),
+            hash("{ user(id: 2) { x } user(id: 1) { x } }").unwrap()
+        );
+        assert_ne!(
+            a,
+            hash("query Q($a: Int, $b: Int) { user { id } }").unwrap()
+        );
+    }
+
+    #[test]
+    fn hash_operations_includes_used_fragments() {
+        let doc = "
+            query A { user { ...UserFields } }
+            query B { user { id } }
+            fragment UserFields on User { id name }
+            fragment Unused on User { email }
+        ";
+        let hashes = hash_operations(doc).unwrap();
+
+        assert_eq!(
+            hashes,
+            vec![
+                OperationHash {
+                    name: Some(String::from("A")),
+                    hash: hash(
+                        "query A { user { ...UserFields } } fragment UserFields on User { name id }"
+                    )
+                    .unwrap()
+                },
+                OperationHash {
+                    name: Some(String::from("B")),
+                    hash: hash("query B { user { id } }").unwrap()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn inline_fragments_replaces_spreads() {
+        let options = NormalizeOptions {
+            inline_fragments: true,
+            ..Default::default()
+        };
+        let result = normalize_with(
+            "
+            query Q { user { ...UserFields } }
+            fragment UserFields on User { id ...UserName ...Node }
+            fragment UserName on User { name }
+            fragment Node on Node { id }
+            ",
+            &options,
+        )
+        .unwrap()
+        .document;
+
+        assert_eq!(
+            result,
+            "query Q {\n  user {\n    ... on User {\n      id\n      name\n      ... on Node {\n        id\n      }\n    }\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn inline_fragments_keeps_spread_directives() {
+        let options = NormalizeOptions {
+            inline_fragments: true,
+            ..Default::default()
+        };
+        let result = normalize_with(
+            "
+            query Q($a: Boolean!) { ... on Query { ...Fields @include(if: $a) } }
+            fragment Fields on Query { version }
+            ",
+            &options,
+        )
+        .unwrap()
+        .document;
+
+        assert_eq!(
+            result,
+            "query Q($a: Boolean!) {\n  ... on Query {\n    ... on Query @include(if: $a) {\n      version\n    }\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn inline_fragments_detects_cycles() {
+        let options = NormalizeOptions {
+            inline_fragments: true,
+            ..Default::default()
+        };
+        let err = normalize_with(
+            "
+            query Q { user { ...A } }
+            fragment A on User { ...B }
+            fragment B on User { ...A }
+            ",
+            &options,
+        )
+        .unwrap_err();
+
+        assert_eq!(err.to_string(), "fragment cycle detected: A -> B -> A");
+    }
+
+    #[test]
+    fn merges_duplicate_fields() {
+        let result = normalize(
+            "
+            {
+              user(id: 1) { name }
+              user(id: 1) { id name }
+              other: user(id: 1) { email }
+              user(id: 2) { email }
+              name
+              name
+            }
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(
+            result,
+            "{\n  name\n  other: user(id: 1) {\n    email\n  }\n  user(id: 1) {\n    id\n    name\n  }\n  user(id: 2) {\n    email\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn merges_inline_fragments_and_spreads() {
+        let result = normalize(
+            "
+            query Q($a: Boolean) {
+              node {
+                ... on User { name }
+                ... on User { id }
+                ... on User @include(if: $a) { email }
+                ...F
+                ...F
+              }
+            }
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(
+            result,
+            "query Q($a: Boolean) {\n  node {\n    ...F\n    ... on User {\n      id\n      name\n    }\n    ... on User @include(if: $a) {\n      email\n    }\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn renames_variables_in_order_of_use() {
+        let options = NormalizeOptions {
+            rename_variables: true,
+            ..Default::default()
+        };
+        let a = normalize_with(
+            "
+            query Q($unused: Int, $userId: ID!, $show: Boolean!, $first: Int = 10) {
+              user(id: $userId) {
+                ...Posts @include(if: $show)
+              }
+            }
+            fragment Posts on User { posts(first: $first, filter: { ids: [$userId] }) { id } }
+            ",
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(
+            a.document,
+            "query Q($v0: ID!, $v1: Boolean!, $v2: Int = 10, $v3: Int) {\n  user(id: $v0) {\n    ...Posts @include(if: $v1)\n  }\n}\n\nfragment Posts on User {\n  posts(filter: {ids: [$v0]}, first: $v2) {\n    id\n  }\n}\n"
+        );
+        assert_eq!(
+            a.variables,
+            vec![RenamedVariables {
+                operation: Some(String::from("Q")),
+                variables: vec![
+                    (String::from("userId"), String::from("v0")),
+                    (String::from("show"), String::from("v1")),
+                    (String::from("first"), String::from("v2")),
+                    (String::from("unused"), String::from("v3")),
+                ],
+            }]
+        );
+
+        let b = normalize_with(
+            "
+            query Q($id: ID!, $s: Boolean!, $n: Int = 10, $x: Int) {
+              user(id: $id) {
+                ...Posts @include(if: $s)
+              }
+            }
+            fragment Posts on User { posts(first: $n, filter: { ids: [$id] }) { id } }
+            ",
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(a.document, b.document);
+    }
+
+    #[test]
+    fn renames_variables_per_operation() {
+        let options = NormalizeOptions {
+            rename_variables: true,
+            ..Default::default()
+        };
+        let alone = normalize_with(
+            "query B($id: ID!) { user(id: $id) { ...Name } } fragment Name on User { name }",
+            &options,
+        )
+        .unwrap();
+        let together = normalize_with(
+            "
+            query A($first: Int, $after: String) { users(first: $first, after: $after) { ...Name } }
+            query B($id: ID!) { user(id: $id) { ...Name } }
+            fragment Name on User { name }
+            ",
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(
+            together.document,
+            "query A($v0: String, $v1: Int) {\n  users(after: $v0, first: $v1) {\n    ...Name\n  }\n}\n\nquery B($v0: ID!) {\n  user(id: $v0) {\n    ...Name\n  }\n}\n\nfragment Name on User {\n  name\n}\n"
+        );
+        assert!(together.document.ends_with(&alone.document));
+        assert_eq!(
+            together.variables,
+            vec![
+                RenamedVariables {
+                    operation: Some(String::from("A")),
+                    variables: vec![
+                        (String::from("after"), String::from("v0")),
+                        (String::from("first"), String::from("v1")),
+                    ],
+                },
+                RenamedVariables {
+                    operation: Some(String::from("B")),
+                    variables: vec![(String::from("id"), String::from("v0"))],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rename_variables_inlines_conflicting_shared_fragments() {
+        let options = NormalizeOptions {
+            rename_variables: true,
+            ..Default::default()
+        };
+        let query = "
+            query A($size: Int) { me { ...Avatar ...Name } }
+            query B($id: ID!, $size: Int) { user(id: $id) { ...Avatar ...Name } }
+            fragment Avatar on User { avatar(size: $size) }
+            fragment Name on User { name }
+            ";
+
+        assert_eq!(
+            normalize_with(query, &options).unwrap().document,
+            "query A($v0: Int) {\n  me {\n    ...Name\n    ... on User {\n      avatar(size: $v0)\n    }\n  }\n}\n\nquery B($v0: ID!, $v1: Int) {\n  user(id: $v0) {\n    ...Name\n    ... on User {\n      avatar(size: $v1)\n    }\n  }\n}\n\nfragment Name on User {\n  name\n}\n"
+        );
+
+        let inlined = NormalizeOptions {
+            inline_fragments: true,
+            ..options.clone()
+        };
+        let query = "
+            query A($size: Int) { me { ...Avatar } }
+            query B($id: ID!, $size: Int) { user(id: $id) { ...Avatar } }
+            fragment Avatar on User { avatar(size: $size) }
+            ";
+        assert_eq!(
+            normalize_with(query, &options).unwrap().document,
+            normalize_with(query, &inlined).unwrap().document
+        );
+        assert_eq!(
+            normalize_with(query, &options).unwrap().document,
+            "query A($v0: Int) {\n  me {\n    ... on User {\n      avatar(size: $v0)\n    }\n  }\n}\n\nquery B($v0: ID!, $v1: Int) {\n  user(id: $v0) {\n    ... on User {\n      avatar(size: $v1)\n    }\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn signature_drops_aliases() {
+        let a = signature("{ me: user { first: name } }").unwrap();
+        let b = signature("{ user { name } }").unwrap();
+
+        assert_eq!(a, b);
+    }
+}
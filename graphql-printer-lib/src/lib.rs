@@ -0,0 +1,64 @@
+mod printer;
+mod query;
+mod schema;
+
+pub use query::print_query;
+pub use schema::print_schema;
+
+/// Options for [`print_schema`] and [`print_query`]. The defaults print
+/// documents exactly like their `Display` implementations do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrintOptions {
+    /// The number of spaces per indentation level, or the width of a tab when
+    /// indenting with tabs.
+    pub indent: usize,
+    /// Indent with tabs instead of spaces.
+    pub tabs: bool,
+    /// Put each argument on its own line when an argument list would make its
+    /// line longer than this.
+    pub max_width: Option<usize>,
+    pub descriptions: DescriptionStyle,
+    /// End the output with a newline.
+    pub trailing_newline: bool,
+    pub commas: CommaStyle,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        PrintOptions {
+            indent: 2,
+            tabs: false,
+            max_width: None,
+            descriptions: DescriptionStyle::default(),
+            trailing_newline: true,
+            commas: CommaStyle::default(),
+        }
+    }
+}
+
+/// How descriptions are quoted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DescriptionStyle {
+    /// Block strings for descriptions spanning several lines, single-line
+    /// strings otherwise.
+    #[default]
+    Auto,
+    /// Always single-line strings, escaping newlines.
+    SingleLine,
+    /// Always block strings on their own lines. Argument lists holding a
+    /// description are put on several lines for them.
+    Block,
+}
+
+/// Where commas separate arguments, variables and the items of list and
+/// object values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CommaStyle {
+    /// Between items on the same line only.
+    #[default]
+    Inline,
+    /// Also at the end of every line but the last of a wrapped list.
+    Always,
+    /// Never, separating items on the same line with spaces.
+    Never,
+}
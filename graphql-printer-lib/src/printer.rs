@@ -0,0 +1,273 @@
+use crate::{CommaStyle, DescriptionStyle, PrintOptions};
+use graphql_parser::query::{Directive, Type, Value};
+use std::fmt::Write;
+
+/// Accumulates printed text, keeping track of the indentation level.
+pub(crate) struct Printer<'o> {
+    options: &'o PrintOptions,
+    buf: String,
+    level: usize,
+}
+
+impl<'o> Printer<'o> {
+    pub fn new(options: &'o PrintOptions) -> Self {
+        Printer {
+            options,
+            buf: String::with_capacity(1024),
+            level: 0,
+        }
+    }
+
+    pub fn finish(mut self) -> String {
+        if !self.options.trailing_newline {
+            while self.buf.ends_with('\n') {
+                self.buf.pop();
+            }
+        }
+        self.buf
+    }
+
+    pub fn write(&mut self, s: &str) {
+        self.buf.push_str(s);
+    }
+
+    pub fn indent(&mut self) {
+        for _ in 0..self.level {
+            if self.options.tabs {
+                self.buf.push('\t');
+            } else {
+                self.buf
+                    .extend(std::iter::repeat_n(' ', self.options.indent));
+            }
+        }
+    }
+
+    pub fn endline(&mut self) {
+        self.buf.push('\n');
+    }
+
+    /// Separates a definition from the previous one with a blank line.
+    pub fn margin(&mut self) {
+        if !self.buf.is_empty() {
+            self.buf.push('\n');
+        }
+    }
+
+    pub fn start_block(&mut self) {
+        self.buf.push('{');
+        self.begin_block();
+    }
+
+    /// Starts a block whose opening brace was already written.
+    pub fn begin_block(&mut self) {
+        self.endline();
+        self.level += 1;
+    }
+
+    pub fn end_block(&mut self) {
+        self.level -= 1;
+        self.indent();
+        self.buf.push('}');
+        self.endline();
+    }
+
+    /// Writes a description on its own line, before the line of the item it
+    /// describes.
+    pub fn description(&mut self, description: &Option<String>) {
+        if let Some(description) = description {
+            self.indent();
+            self.quoted(description, self.options.descriptions);
+            self.endline();
+        }
+    }
+
+    /// Writes a description in front of the item it describes, or on its own
+    /// line for block descriptions.
+    pub fn inline_description(&mut self, description: &Option<String>) {
+        if let Some(description) = description {
+            self.quoted(description, self.options.descriptions);
+            if self.options.descriptions == DescriptionStyle::Block {
+                self.endline();
+                self.indent();
+            } else {
+                self.buf.push(' ');
+            }
+        }
+    }
+
+    /// Whether argument lists holding descriptions have to be put on several
+    /// lines.
+    pub fn block_descriptions(&self) -> bool {
+        self.options.descriptions == DescriptionStyle::Block
+    }
+
+    fn quoted(&mut self, s: &str, style: DescriptionStyle) {
+        let has_newline = s.contains('\n');
+        let has_nonprintable = s
+            .chars()
+            .any(|c| c < '\u{0020}' && !matches!(c, '\n' | '\r' | '\t'));
+        let block = match style {
+            DescriptionStyle::Auto => has_newline && !has_nonprintable,
+            DescriptionStyle::SingleLine => false,
+            DescriptionStyle::Block => !has_nonprintable,
+        };
+
+        if !block {
+            self.buf.push('"');
+            for c in s.chars() {
+                match c {
+                    '\r' => self.write(r"\r"),
+                    '\n' => self.write(r"\n"),
+                    '\t' => self.write(r"\t"),
+                    '"' => self.write("\\\""),
+                    '\\' => self.write(r"\\"),
+                    c if c < '\u{0020}' => write!(self.buf, "\\u{:04X}", c as u32).unwrap(),
+                    c => self.buf.push(c),
+                }
+            }
+            self.buf.push('"');
+        } else {
+            self.write(r#"""""#);
+            self.endline();
+            self.level += 1;
+            for line in s.lines() {
+                if !line.trim().is_empty() {
+                    self.indent();
+                    self.write(&line.replace(r#"""""#, r#"\""""#));
+                }
+                self.endline();
+            }
+            self.level -= 1;
+            self.indent();
+            self.write(r#"""""#);
+        }
+    }
+
+    /// The width of the line being written, counting tabs as a level of
+    /// indentation.
+    fn line_width(&self) -> usize {
+        let line = &self.buf[self.buf.rfind('\n').map_or(0, |i| i + 1)..];
+        line.chars()
+            .map(|c| if c == '\t' { self.options.indent } else { 1 })
+            .sum()
+    }
+
+    /// Writes items between `open` and `close` on the current line.
+    pub fn list<T>(
+        &mut self,
+        open: char,
+        close: char,
+        items: &[T],
+        mut item: impl FnMut(&mut Self, &T),
+    ) {
+        self.buf.push(open);
+        for (i, it) in items.iter().enumerate() {
+            if i > 0 {
+                match self.options.commas {
+                    CommaStyle::Never => self.buf.push(' '),
+                    CommaStyle::Inline | CommaStyle::Always => self.write(", "),
+                }
+            }
+            item(self, it);
+        }
+        self.buf.push(close);
+    }
+
+    /// Writes a parenthesized argument list followed by `rest`, the remainder
+    /// of its line. The arguments are put on lines of their own when `wrap` is
+    /// set or the line would be wider than the maximum width.
+    pub fn arguments<T>(
+        &mut self,
+        items: &[T],
+        wrap: bool,
+        mut item: impl FnMut(&mut Self, &T),
+        mut rest: impl FnMut(&mut Self),
+    ) {
+        if items.is_empty() {
+            rest(self);
+            return;
+        }
+
+        let start = self.buf.len();
+        if !wrap {
+            self.list('(', ')', items, &mut item);
+            rest(self);
+            if self
+                .options
+                .max_width
+                .is_none_or(|max| self.line_width() <= max)
+            {
+                return;
+            }
+            self.buf.truncate(start);
+        }
+
+        self.buf.push('(');
+        self.begin_block();
+        for (i, it) in items.iter().enumerate() {
+            self.indent();
+            item(self, it);
+            if self.options.commas == CommaStyle::Always && i + 1 < items.len() {
+                self.buf.push(',');
+            }
+            self.endline();
+        }
+        self.level -= 1;
+        self.indent();
+        self.buf.push(')');
+        rest(self);
+    }
+
+    pub fn directives(&mut self, directives: &[Directive<'_, String>]) {
+        for directive in directives {
+            self.write(" @");
+            self.write(&directive.name);
+            if !directive.arguments.is_empty() {
+                self.list('(', ')', &directive.arguments, |p, (name, value)| {
+                    p.write(name);
+                    p.write(": ");
+                    p.value(value);
+                });
+            }
+        }
+    }
+
+    pub fn value_type(&mut self, value_type: &Type<'_, String>) {
+        match value_type {
+            Type::NamedType(name) => self.write(name),
+            Type::ListType(inner) => {
+                self.buf.push('[');
+                self.value_type(inner);
+                self.buf.push(']');
+            }
+            Type::NonNullType(inner) => {
+                self.value_type(inner);
+                self.buf.push('!');
+            }
+        }
+    }
+
+    pub fn value(&mut self, value: &Value<'_, String>) {
+        match value {
+            Value::Variable(name) => {
+                self.buf.push('$');
+                self.write(name);
+            }
+            Value::Int(number) => write!(self.buf, "{}", number.as_i64().unwrap()).unwrap(),
+            Value::Float(number) => write!(self.buf, "{number}").unwrap(),
+            Value::String(s) => self.quoted(s, DescriptionStyle::Auto),
+            Value::Boolean(b) => write!(self.buf, "{b}").unwrap(),
+            Value::Null => self.write("null"),
+            Value::Enum(name) => self.write(name),
+            Value::List(items) => self.list('[', ']', items, |p, item| p.value(item)),
+            Value::Object(fields) => {
+                let fields: Vec<_> = fields.iter().collect();
+                self.list('{', '}', &fields, |p, (name, value)| {
+                    p.write(name);
+                    p.write(": ");
+                    p.value(value);
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use crate::printer::Printer;
+use crate::PrintOptions;
+use graphql_parser::query::{
+    Definition, Document, FragmentDefinition, OperationDefinition, Selection, SelectionSet,
+    TypeCondition, VariableDefinition,
+};
+
+/// Prints a query document.
+pub fn print_query(query_ast: &Document<'_, String>, options: &PrintOptions) -> String {
+    let mut p = Printer::new(options);
+
+    for definition in &query_ast.definitions {
+        p.margin();
+        match definition {
+            Definition::Operation(operation) => operation_definition(&mut p, operation),
+            Definition::Fragment(fragment) => fragment_definition(&mut p, fragment),
+        }
+    }
+
+    p.finish()
+}
+
+fn operation_definition(p: &mut Printer, operation: &OperationDefinition<'_, String>) {
+    let (keyword, name, variables, directives, selection_set) = match operation {
+        OperationDefinition::SelectionSet(selection_set) => {
+            p.indent();
+            p.start_block();
+            selections(p, selection_set);
+            p.end_block();
+            return;
+        }
+        OperationDefinition::Query(query) => (
+            "query",
+            &query.name,
+            &query.variable_definitions,
+            &query.directives,
+            &query.selection_set,
+        ),
+        OperationDefinition::Mutation(mutation) => (
+            "mutation",
+            &mutation.name,
+            &mutation.variable_definitions,
+            &mutation.directives,
+            &mutation.selection_set,
+        ),
+        OperationDefinition::Subscription(subscription) => (
+            "subscription",
+            &subscription.name,
+            &subscription.variable_definitions,
+            &subscription.directives,
+            &subscription.selection_set,
+        ),
+    };
+
+    p.indent();
+    p.write(keyword);
+    if let Some(name) = name {
+        p.write(" ");
+        p.write(name);
+    }
+    p.arguments(variables, false, variable_definition, |p| {
+        p.directives(directives);
+        p.write(" {");
+    });
+    p.begin_block();
+    selections(p, selection_set);
+    p.end_block();
+}
+
+fn fragment_definition(p: &mut Printer, fragment: &FragmentDefinition<'_, String>) {
+    p.indent();
+    p.write("fragment ");
+    p.write(&fragment.name);
+    p.write(" ");
+    type_condition(p, &fragment.type_condition);
+    p.directives(&fragment.directives);
+    p.write(" ");
+    p.start_block();
+    selections(p, &fragment.selection_set);
+    p.end_block();
+}
+
+fn variable_definition(p: &mut Printer, variable: &VariableDefinition<'_, String>) {
+    p.write("$");
+    p.write(&variable.name);
+    p.write(": ");
+    p.value_type(&variable.var_type);
+    if let Some(default) = &variable.default_value {
+        p.write(" = ");
+        p.value(default);
+    }
+}
+
+fn selections(p: &mut Printer, selection_set: &SelectionSet<'_, String>) {
+    for selection in &selection_set.items {
+        p.indent();
+        match selection {
+            Selection::Field(field) => {
+                if let Some(alias) = &field.alias {
+                    p.write(alias);
+                    p.write(": ");
+                }
+                p.write(&field.name);
+                let nested = !field.selection_set.items.is_empty();
+                p.arguments(
+                    &field.arguments,
+                    false,
+                    |p, (name, value)| {
+                        p.write(name);
+                        p.write(": ");
+                        p.value(value);
+                    },
+                    |p| {
+                        p.directives(&field.directives);
+                        if nested {
+                            p.write(" {");
+                        }
+                    },
+                );
+                if nested {
+                    p.begin_block();
+                    selections(p, &field.selection_set);
+                    p.end_block();
+                } else {
+                    p.endline();
+                }
+            }
+            Selection::InlineFragment(fragment) => {
+                p.write("...");
+                if let Some(condition) = &fragment.type_condition {
+                    p.write(" ");
+                    type_condition(p, condition);
+                }
+                p.directives(&fragment.directives);
+                p.write(" ");
+                p.start_block();
+                selections(p, &fragment.selection_set);
+                p.end_block();
+            }
+            Selection::FragmentSpread(spread) => {
+                p.write("...");
+                p.write(&spread.fragment_name);
+                p.directives(&spread.directives);
+                p.endline();
+            }
+        }
+    }
+}
+
+fn type_condition(p: &mut Printer, condition: &TypeCondition<'_, String>) {
+    let TypeCondition::On(name) = condition;
+    p.write("on ");
+    p.write(name);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::print_query;
+    use crate::{CommaStyle, PrintOptions};
+    use graphql_parser::parse_query;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    const QUERY: &str = indoc! {r#"
+        query GetUser($id: ID!, $first: Int = 10, $filter: Filter = {tags: ["a", "b"], exact: true}) @cached(ttl: 60) {
+          user(id: $id) {
+            handle: name
+            posts(first: $first, orderBy: {field: CREATED_AT, direction: DESC}, after: null) @include(if: true) {
+              ...PostFields
+              ... on Article {
+                body(format: "markdown")
+              }
+              ... @skip(if: false) {
+                score(weight: 1.5)
+              }
+            }
+          }
+        }
+
+        fragment PostFields on Post @deprecated {
+          id
+        }
+
+        {
+          version
+        }
+
+        mutation {
+          logout
+        }
+
+        subscription OnEvent($kind: Kind) {
+          event(kind: $kind)
+        }
+    "#};
+
+    #[test]
+    fn prints_like_display_by_default() {
+        let query = parse_query::<String>(QUERY).unwrap();
+        assert_eq!(
+            print_query(&query, &PrintOptions::default()),
+            query.to_string()
+        );
+    }
+
+    #[test]
+    fn wraps_long_arguments_and_variables() {
+        let query = parse_query::<String>(QUERY).unwrap();
+        let options = PrintOptions {
+            indent: 4,
+            max_width: Some(80),
+            commas: CommaStyle::Never,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            print_query(&query, &options).split("\n\n").next().unwrap(),
+            indoc! {r#"
+                query GetUser(
+                    $id: ID!
+                    $first: Int = 10
+                    $filter: Filter = {exact: true tags: ["a" "b"]}
+                ) @cached(ttl: 60) {
+                    user(id: $id) {
+                        handle: name
+                        posts(
+                            first: $first
+                            orderBy: {direction: DESC field: CREATED_AT}
+                            after: null
+                        ) @include(if: true) {
+                            ...PostFields
+                            ... on Article {
+                                body(format: "markdown")
+                            }
+                            ... @skip(if: false) {
+                                score(weight: 1.5)
+                            }
+                        }
+                    }
+                }"#}
+        );
+    }
+}
@@ -0,0 +1,391 @@
+use crate::printer::Printer;
+use crate::PrintOptions;
+use graphql_parser::schema::{
+    Definition, DirectiveDefinition, Document, EnumValue, Field, InputValue, SchemaDefinition,
+    TypeDefinition, TypeExtension,
+};
+
+/// Prints a schema document.
+pub fn print_schema(schema_ast: &Document<'_, String>, options: &PrintOptions) -> String {
+    let mut p = Printer::new(options);
+
+    for definition in &schema_ast.definitions {
+        p.margin();
+        match definition {
+            Definition::SchemaDefinition(schema) => schema_definition(&mut p, schema),
+            Definition::TypeDefinition(td) => type_definition(&mut p, td),
+            Definition::TypeExtension(ext) => type_extension(&mut p, ext),
+            Definition::DirectiveDefinition(directive) => directive_definition(&mut p, directive),
+        }
+    }
+
+    p.finish()
+}
+
+fn schema_definition(p: &mut Printer, schema: &SchemaDefinition<'_, String>) {
+    p.indent();
+    p.write("schema");
+    p.directives(&schema.directives);
+    p.write(" ");
+    p.start_block();
+    let operations = [
+        ("query", &schema.query),
+        ("mutation", &schema.mutation),
+        ("subscription", &schema.subscription),
+    ];
+    for (operation, name) in operations {
+        if let Some(name) = name {
+            p.indent();
+            p.write(operation);
+            p.write(": ");
+            p.write(name);
+            p.endline();
+        }
+    }
+    p.end_block();
+}
+
+fn type_definition(p: &mut Printer, td: &TypeDefinition<'_, String>) {
+    match td {
+        TypeDefinition::Scalar(scalar) => {
+            p.description(&scalar.description);
+            p.indent();
+            p.write("scalar ");
+            p.write(&scalar.name);
+            p.directives(&scalar.directives);
+            p.endline();
+        }
+        TypeDefinition::Object(object) => {
+            p.description(&object.description);
+            p.indent();
+            p.write("type ");
+            p.write(&object.name);
+            implements(p, &object.implements_interfaces);
+            p.directives(&object.directives);
+            fields(p, &object.fields);
+        }
+        TypeDefinition::Interface(interface) => {
+            p.description(&interface.description);
+            p.indent();
+            p.write("interface ");
+            p.write(&interface.name);
+            implements(p, &interface.implements_interfaces);
+            p.directives(&interface.directives);
+            fields(p, &interface.fields);
+        }
+        TypeDefinition::Union(union) => {
+            p.description(&union.description);
+            p.indent();
+            p.write("union ");
+            p.write(&union.name);
+            p.directives(&union.directives);
+            members(p, &union.types);
+        }
+        TypeDefinition::Enum(enum_type) => {
+            p.description(&enum_type.description);
+            p.indent();
+            p.write("enum ");
+            p.write(&enum_type.name);
+            p.directives(&enum_type.directives);
+            enum_values(p, &enum_type.values);
+        }
+        TypeDefinition::InputObject(input) => {
+            p.description(&input.description);
+            p.indent();
+            p.write("input ");
+            p.write(&input.name);
+            p.directives(&input.directives);
+            input_fields(p, &input.fields);
+        }
+    }
+}
+
+fn type_extension(p: &mut Printer, ext: &TypeExtension<'_, String>) {
+    p.indent();
+    match ext {
+        TypeExtension::Scalar(scalar) => {
+            p.write("extend scalar ");
+            p.write(&scalar.name);
+            p.directives(&scalar.directives);
+            p.endline();
+        }
+        TypeExtension::Object(object) => {
+            p.write("extend type ");
+            p.write(&object.name);
+            implements(p, &object.implements_interfaces);
+            p.directives(&object.directives);
+            fields(p, &object.fields);
+        }
+        TypeExtension::Interface(interface) => {
+            p.write("extend interface ");
+            p.write(&interface.name);
+            implements(p, &interface.implements_interfaces);
+            p.directives(&interface.directives);
+            fields(p, &interface.fields);
+        }
+        TypeExtension::Union(union) => {
+            p.write("extend union ");
+            p.write(&union.name);
+            p.directives(&union.directives);
+            members(p, &union.types);
+        }
+        TypeExtension::Enum(enum_type) => {
+            p.write("extend enum ");
+            p.write(&enum_type.name);
+            p.directives(&enum_type.directives);
+            enum_values(p, &enum_type.values);
+        }
+        TypeExtension::InputObject(input) => {
+            p.write("extend input ");
+            p.write(&input.name);
+            p.directives(&input.directives);
+            input_fields(p, &input.fields);
+        }
+    }
+}
+
+fn directive_definition(p: &mut Printer, directive: &DirectiveDefinition<'_, String>) {
+    p.description(&directive.description);
+    p.indent();
+    p.write("directive @");
+    p.write(&directive.name);
+    let wrap = has_descriptions(p, &directive.arguments);
+    p.arguments(&directive.arguments, wrap, input_value, |p| {
+        if directive.repeatable {
+            p.write(" repeatable");
+        }
+        for (i, location) in directive.locations.iter().enumerate() {
+            p.write(if i == 0 { " on " } else { " | " });
+            p.write(location.as_str());
+        }
+    });
+    p.endline();
+}
+
+fn implements(p: &mut Printer, interfaces: &[String]) {
+    for (i, interface) in interfaces.iter().enumerate() {
+        p.write(if i == 0 { " implements " } else { " & " });
+        p.write(interface);
+    }
+}
+
+fn members(p: &mut Printer, types: &[String]) {
+    for (i, member) in types.iter().enumerate() {
+        p.write(if i == 0 { " = " } else { " | " });
+        p.write(member);
+    }
+    p.endline();
+}
+
+fn fields(p: &mut Printer, fields: &[Field<'_, String>]) {
+    if fields.is_empty() {
+        p.endline();
+        return;
+    }
+
+    p.write(" ");
+    p.start_block();
+    for field in fields {
+        p.description(&field.description);
+        p.indent();
+        p.write(&field.name);
+        let wrap = has_descriptions(p, &field.arguments);
+        p.arguments(&field.arguments, wrap, input_value, |p| {
+            p.write(": ");
+            p.value_type(&field.field_type);
+            p.directives(&field.directives);
+        });
+        p.endline();
+    }
+    p.end_block();
+}
+
+fn input_fields(p: &mut Printer, fields: &[InputValue<'_, String>]) {
+    if fields.is_empty() {
+        p.endline();
+        return;
+    }
+
+    p.write(" ");
+    p.start_block();
+    for field in fields {
+        p.indent();
+        input_value(p, field);
+        p.endline();
+    }
+    p.end_block();
+}
+
+fn enum_values(p: &mut Printer, values: &[EnumValue<'_, String>]) {
+    if values.is_empty() {
+        p.endline();
+        return;
+    }
+
+    p.write(" ");
+    p.start_block();
+    for value in values {
+        p.indent();
+        p.inline_description(&value.description);
+        p.write(&value.name);
+        p.directives(&value.directives);
+        p.endline();
+    }
+    p.end_block();
+}
+
+fn input_value(p: &mut Printer, value: &InputValue<'_, String>) {
+    p.inline_description(&value.description);
+    p.write(&value.name);
+    p.write(": ");
+    p.value_type(&value.value_type);
+    if let Some(default) = &value.default_value {
+        p.write(" = ");
+        p.value(default);
+    }
+    p.directives(&value.directives);
+}
+
+/// Block descriptions cannot share a line with the other arguments.
+fn has_descriptions(p: &Printer, arguments: &[InputValue<'_, String>]) -> bool {
+    p.block_descriptions() && arguments.iter().any(|arg| arg.description.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::print_schema;
+    use crate::{CommaStyle, DescriptionStyle, PrintOptions};
+    use graphql_parser::parse_schema;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    const SCHEMA: &str = indoc! {r#"
+        schema @link(url: "https://example.com") {
+          query: Query
+          mutation: Mutation
+        }
+
+        "Marks a field as requiring a role"
+        directive @auth(role: Role = ADMIN, scopes: [String!] = ["read", "write"]) repeatable on FIELD_DEFINITION | OBJECT
+
+        """
+        The entry point
+        of every query
+        """
+        type Query implements Node & Entity @auth(role: USER) {
+          "A user by id"
+          user(id: ID!, "Include deleted users" deleted: Boolean = false): User @deprecated(reason: "Use node")
+          users(filter: UserFilter = {name: "a", limit: 10}, first: Float = 1.5): [User!]!
+        }
+
+        type Mutation
+
+        scalar Date @specifiedBy(url: "https://example.com/date")
+
+        union SearchResult @auth = User | Post
+
+        enum Role {
+          "Everyone"
+          USER
+          ADMIN @deprecated
+        }
+
+        input UserFilter {
+          "Matches the start of the name"
+          name: String = null
+          limit: Int
+        }
+
+        extend type Query {
+          me: User
+        }
+
+        extend union SearchResult = Comment
+
+        extend enum Role {
+          OWNER
+        }
+
+        extend scalar Date @auth
+    "#};
+
+    fn process(options: &PrintOptions) -> String {
+        let schema = parse_schema::<String>(SCHEMA).unwrap();
+        print_schema(&schema, options)
+    }
+
+    #[test]
+    fn prints_like_display_by_default() {
+        let schema = parse_schema::<String>(SCHEMA).unwrap();
+        assert_eq!(process(&PrintOptions::default()), schema.to_string());
+    }
+
+    #[test]
+    fn indents_with_tabs_and_wraps_long_arguments() {
+        let options = PrintOptions {
+            tabs: true,
+            max_width: Some(60),
+            commas: CommaStyle::Always,
+            trailing_newline: false,
+            ..Default::default()
+        };
+        let printed = process(&options);
+        let query = printed
+            .split("\n\n")
+            .find(|definition| definition.contains("type Query implements"))
+            .unwrap();
+
+        assert_eq!(
+            query,
+            indoc! {r#"
+                """
+                	The entry point
+                	of every query
+                """
+                type Query implements Node & Entity @auth(role: USER) {
+                	"A user by id"
+                	user(
+                		id: ID!,
+                		"Include deleted users" deleted: Boolean = false
+                	): User @deprecated(reason: "Use node")
+                	users(
+                		filter: UserFilter = {limit: 10, name: "a"},
+                		first: Float = 1.5
+                	): [User!]!
+                }"#}
+        );
+        assert!(printed.ends_with("extend scalar Date @auth"));
+    }
+
+    #[test]
+    fn prints_block_descriptions_without_commas() {
+        let options = PrintOptions {
+            indent: 4,
+            descriptions: DescriptionStyle::Block,
+            commas: CommaStyle::Never,
+            ..Default::default()
+        };
+        let printed = process(&options);
+        let sections: Vec<&str> = printed.split("\n\n").collect();
+
+        assert_eq!(
+            sections[1],
+            indoc! {r#"
+                """
+                    Marks a field as requiring a role
+                """
+                directive @auth(role: Role = ADMIN scopes: [String!] = ["read" "write"]) repeatable on FIELD_DEFINITION | OBJECT"#}
+        );
+        assert_eq!(
+            sections[7],
+            indoc! {r#"
+                input UserFilter {
+                    """
+                        Matches the start of the name
+                    """
+                    name: String = null
+                    limit: Int
+                }"#}
+        );
+    }
+}
@@ -0,0 +1,643 @@
+use graphql_parser::{
+    parse_schema,
+    schema::{Definition, Document, Field, InputValue, TypeDefinition, TypeExtension},
+    Pos,
+};
+use graphql_printer::{print_schema, PrintOptions};
+use std::collections::HashMap;
+
+/// The `#` comments of a schema, attached to the definitions, fields,
+/// arguments and enum values around them so that they can be printed again
+/// once the schema is formatted or sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Comments {
+    /// Comments at the top of the file, separated from the first definition
+    /// by a blank line.
+    header: Vec<String>,
+    /// Comments after the last definition.
+    footer: Vec<String>,
+    /// The comments around each node, by coordinate.
+    nodes: HashMap<String, Attached>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Attached {
+    /// Comment lines right before the node.
+    leading: Vec<String>,
+    /// Comments at the end of the node's first line.
+    trailing: Vec<String>,
+    /// Comment lines at the end of a definition's body.
+    dangling: Vec<String>,
+    /// Comments at the end of a definition's last line.
+    closing: Vec<String>,
+}
+
+impl Comments {
+    /// Collects the comments of `source`, which `schema_ast` was parsed
+    /// from. Each comment is attached to the node it precedes or ends the
+    /// line of, comments at the end of a body to the definition, and the
+    /// rest to the top or bottom of the file.
+    pub fn extract(source: &str, schema_ast: &Document<'_, String>) -> Self {
+        let tokens = tokenize(source);
+        let nodes = nodes(schema_ast);
+        // Fields and values are positioned at their description, so comments
+        // are matched with the name that follows instead.
+        let names: Vec<(usize, usize)> = nodes
+            .iter()
+            .map(|node| {
+                let (_, name) = locate(&tokens, node);
+                (tokens[name].line, tokens[name].column)
+            })
+            .collect();
+        let lines: Vec<&str> = source.lines().collect();
+        let mut comments = Comments::default();
+
+        for (i, token) in tokens.iter().enumerate() {
+            if token.kind != Kind::Comment {
+                continue;
+            }
+            let text = token.text.trim().to_string();
+            let position = (token.line, token.column);
+            let enclosing = nodes
+                .iter()
+                .rev()
+                .find(|node| node.definition && node.position <= position);
+
+            let previous = tokens[..i].iter().rev().find(|t| t.kind != Kind::Comment);
+            if let Some(previous) = previous.filter(|t| t.end_line == token.line) {
+                let closes_definition = previous.kind == Kind::Punct('}') && token.depth == 0;
+                let node = nodes.iter().rev().find(|node| node.position < position);
+                match (enclosing, node) {
+                    (Some(enclosing), _) if closes_definition => {
+                        comments.entry(enclosing).closing.push(text);
+                    }
+                    (_, Some(node)) => comments.entry(node).trailing.push(text),
+                    (_, None) => comments.header.push(text),
+                }
+                continue;
+            }
+
+            let Some(next) = tokens[i + 1..].iter().find(|t| t.kind != Kind::Comment) else {
+                comments.footer.push(text);
+                continue;
+            };
+            let next_position = (next.line, next.column);
+            let node = nodes
+                .iter()
+                .zip(&names)
+                .find(|(_, name)| **name >= next_position)
+                .map(|(node, _)| node);
+            let closes = matches!(next.kind, Kind::Punct('}' | ')' | ']'));
+            match (enclosing, node) {
+                (Some(enclosing), node)
+                    if closes || (token.depth > 0 && node.is_none_or(|n| n.definition)) =>
+                {
+                    comments.entry(enclosing).dangling.push(text);
+                }
+                (None, _)
+                    if lines[token.line..next.line - 1]
+                        .iter()
+                        .any(|line| line.trim().is_empty()) =>
+                {
+                    comments.header.push(text);
+                }
+                (_, Some(node)) => comments.entry(node).leading.push(text),
+                (_, None) => comments.footer.push(text),
+            }
+        }
+
+        comments
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.header.is_empty() && self.footer.is_empty() && self.nodes.is_empty()
+    }
+
+    /// Prints the schema, putting back the comments of the nodes it still
+    /// has.
+    pub fn print(&self, schema_ast: &Document<'_, String>, options: &PrintOptions) -> String {
+        let text = print_schema(schema_ast, options);
+        if self.is_empty() {
+            return text;
+        }
+
+        // The printed schema is parsed again to find where each node ended up.
+        let printed = parse_schema::<String>(&text).expect("Printed schema should parse");
+        let tokens = tokenize(&text);
+        let nodes = nodes(&printed);
+        let lines: Vec<&str> = text.lines().collect();
+        let indentation = |line: usize| {
+            let line = lines[line - 1];
+            &line[..line.len() - line.trim_start().len()]
+        };
+
+        let unit = if options.tabs {
+            "\t".to_string()
+        } else {
+            " ".repeat(options.indent)
+        };
+
+        let located: Vec<(usize, usize)> = nodes.iter().map(|n| locate(&tokens, n)).collect();
+        let mut before: HashMap<usize, Vec<String>> = HashMap::new();
+        let mut after: HashMap<usize, Vec<&str>> = HashMap::new();
+        for (i, node) in nodes.iter().enumerate() {
+            let Some(attached) = self.nodes.get(&node.key) else {
+                continue;
+            };
+            let (head, name) = located[i];
+
+            let head_line = tokens[head].line;
+            let indent = indentation(head_line);
+            before
+                .entry(head_line)
+                .or_default()
+                .extend(attached.leading.iter().map(|c| format!("{indent}{c}")));
+            after
+                .entry(tokens[name].line)
+                .or_default()
+                .extend(attached.trailing.iter().map(String::as_str));
+
+            if node.definition {
+                let end = nodes[i + 1..]
+                    .iter()
+                    .zip(&located[i + 1..])
+                    .find(|(next, _)| next.definition)
+                    .map_or(tokens.len(), |(_, (next_head, _))| *next_head)
+                    - 1;
+                let closing_line = tokens[end].end_line;
+                let indent = format!("{}{unit}", indentation(closing_line));
+                before
+                    .entry(closing_line)
+                    .or_default()
+                    .extend(attached.dangling.iter().map(|c| format!("{indent}{c}")));
+                after
+                    .entry(closing_line)
+                    .or_default()
+                    .extend(attached.closing.iter().map(String::as_str));
+            }
+        }
+
+        let mut out = String::new();
+        for comment in &self.header {
+            out.push_str(comment);
+            out.push('\n');
+        }
+        if !self.header.is_empty() {
+            out.push('\n');
+        }
+        for (i, line) in lines.iter().enumerate() {
+            for comment in before.get(&(i + 1)).into_iter().flatten() {
+                out.push_str(comment);
+                out.push('\n');
+            }
+            out.push_str(line);
+            for comment in after.get(&(i + 1)).into_iter().flatten() {
+                out.push(' ');
+                out.push_str(comment);
+            }
+            out.push('\n');
+        }
+        if !self.footer.is_empty() {
+            out.push('\n');
+        }
+        for comment in &self.footer {
+            out.push_str(comment);
+            out.push('\n');
+        }
+        if !options.trailing_newline {
+            out.pop();
+        }
+        out
+    }
+
+    fn entry(&mut self, node: &Node) -> &mut Attached {
+        self.nodes.entry(node.key.clone()).or_default()
+    }
+}
+
+/// A definition, field, argument or enum value comments can attach to.
+struct Node {
+    /// The coordinate of the node, such as `User.name`, `@auth(role:)` or
+    /// `extend User 0` for the first extension of `User`.
+    key: String,
+    /// Where the parser placed the node, which is after the description of
+    /// definitions but before that of fields and values.
+    position: (usize, usize),
+    described: bool,
+    definition: bool,
+}
+
+impl Node {
+    fn new(key: String, position: Pos, described: bool, definition: bool) -> Self {
+        Node {
+            key,
+            position: (position.line, position.column),
+            described,
+            definition,
+        }
+    }
+}
+
+/// Lists the nodes of the schema in document order.
+fn nodes(schema_ast: &Document<'_, String>) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    let mut extensions: HashMap<&String, usize> = HashMap::new();
+
+    for definition in &schema_ast.definitions {
+        match definition {
+            Definition::SchemaDefinition(schema) => {
+                nodes.push(Node::new(
+                    "schema".to_string(),
+                    schema.position,
+                    false,
+                    true,
+                ));
+            }
+            Definition::DirectiveDefinition(directive) => {
+                let key = format!("@{}", directive.name);
+                nodes.push(Node::new(
+                    key.clone(),
+                    directive.position,
+                    directive.description.is_some(),
+                    true,
+                ));
+                add_arguments(&mut nodes, &key, &directive.arguments);
+            }
+            Definition::TypeDefinition(td) => {
+                let (name, position, described) = match td {
+                    TypeDefinition::Scalar(t) => (&t.name, t.position, t.description.is_some()),
+                    TypeDefinition::Object(t) => (&t.name, t.position, t.description.is_some()),
+                    TypeDefinition::Interface(t) => (&t.name, t.position, t.description.is_some()),
+                    TypeDefinition::Union(t) => (&t.name, t.position, t.description.is_some()),
+                    TypeDefinition::Enum(t) => (&t.name, t.position, t.description.is_some()),
+                    TypeDefinition::InputObject(t) => {
+                        (&t.name, t.position, t.description.is_some())
+                    }
+                };
+                nodes.push(Node::new(name.clone(), position, described, true));
+                match td {
+                    TypeDefinition::Object(t) => add_fields(&mut nodes, name, &t.fields),
+                    TypeDefinition::Interface(t) => add_fields(&mut nodes, name, &t.fields),
+                    TypeDefinition::Enum(t) => {
+                        for value in &t.values {
+                            nodes.push(Node::new(
+                                format!("{name}.{}", value.name),
+                                value.position,
+                                value.description.is_some(),
+                                false,
+                            ));
+                        }
+                    }
+                    TypeDefinition::InputObject(t) => add_input_values(&mut nodes, name, &t.fields),
+                    _ => (),
+                }
+            }
+            Definition::TypeExtension(ext) => {
+                let (name, position) = match ext {
+                    TypeExtension::Scalar(t) => (&t.name, t.position),
+                    TypeExtension::Object(t) => (&t.name, t.position),
+                    TypeExtension::Interface(t) => (&t.name, t.position),
+                    TypeExtension::Union(t) => (&t.name, t.position),
+                    TypeExtension::Enum(t) => (&t.name, t.position),
+                    TypeExtension::InputObject(t) => (&t.name, t.position),
+                };
+                let count = extensions.entry(name).or_default();
+                let key = format!("extend {name} {count}");
+                *count += 1;
+                nodes.push(Node::new(key.clone(), position, false, true));
+                match ext {
+                    TypeExtension::Object(t) => add_fields(&mut nodes, &key, &t.fields),
+                    TypeExtension::Interface(t) => add_fields(&mut nodes, &key, &t.fields),
+                    TypeExtension::Enum(t) => {
+                        for value in &t.values {
+                            nodes.push(Node::new(
+                                format!("{key}.{}", value.name),
+                                value.position,
+                                value.description.is_some(),
+                                false,
+                            ));
+                        }
+                    }
+                    TypeExtension::InputObject(t) => add_input_values(&mut nodes, &key, &t.fields),
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    nodes
+}
+
+fn add_fields(nodes: &mut Vec<Node>, parent: &str, fields: &[Field<'_, String>]) {
+    for field in fields {
+        let key = format!("{parent}.{}", field.name);
+        nodes.push(Node::new(
+            key.clone(),
+            field.position,
+            field.description.is_some(),
+            false,
+        ));
+        add_arguments(nodes, &key, &field.arguments);
+    }
+}
+
+fn add_arguments(nodes: &mut Vec<Node>, parent: &str, arguments: &[InputValue<'_, String>]) {
+    for argument in arguments {
+        nodes.push(Node::new(
+            format!("{parent}({}:)", argument.name),
+            argument.position,
+            argument.description.is_some(),
+            false,
+        ));
+    }
+}
+
+fn add_input_values(nodes: &mut Vec<Node>, parent: &str, values: &[InputValue<'_, String>]) {
+    for value in values {
+        nodes.push(Node::new(
+            format!("{parent}.{}", value.name),
+            value.position,
+            value.description.is_some(),
+            false,
+        ));
+    }
+}
+
+/// Finds the token a node starts at, including its description, and the
+/// token of its name, skipping any comment in between.
+fn locate(tokens: &[Token], node: &Node) -> (usize, usize) {
+    let start = tokens
+        .iter()
+        .position(|t| t.kind != Kind::Comment && (t.line, t.column) >= node.position)
+        .unwrap_or(tokens.len() - 1);
+    let head = if node.described && tokens[start].kind != Kind::String {
+        start - 1
+    } else {
+        start
+    };
+    let name = (start..tokens.len())
+        .find(|i| !matches!(tokens[*i].kind, Kind::String | Kind::Comment))
+        .unwrap_or(start);
+    (head, name)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Comment,
+    String,
+    Punct(char),
+    Name,
+}
+
+/// A lexical token, positioned the way `graphql_parser` positions nodes.
+struct Token<'t> {
+    kind: Kind,
+    text: &'t str,
+    line: usize,
+    column: usize,
+    end_line: usize,
+    /// The number of braces open around the token.
+    depth: usize,
+}
+
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut lexer = Lexer {
+        text,
+        offset: 0,
+        line: 1,
+        column: 1,
+    };
+    let mut tokens = Vec::new();
+    let mut depth = 0;
+
+    while let Some(c) = lexer.peek() {
+        let (start, line, column) = (lexer.offset, lexer.line, lexer.column);
+        let kind = match c {
+            '\t' => {
+                lexer.bump();
+                lexer.column += 7;
+                continue;
+            }
+            ',' => {
+                lexer.bump();
+                continue;
+            }
+            c if c.is_whitespace() || c == '\u{feff}' => {
+                lexer.bump();
+                continue;
+            }
+            '#' => {
+                lexer.bump_while(|c| c != '\n');
+                Kind::Comment
+            }
+            '"' if lexer.rest().starts_with(r#"""""#) => {
+                lexer.bump_n(3);
+                while !lexer.rest().is_empty() && !lexer.rest().starts_with(r#"""""#) {
+                    let escaped = lexer.rest().starts_with(r#"\""""#);
+                    lexer.bump_n(if escaped { 4 } else { 1 });
+                }
+                lexer.bump_n(3);
+                Kind::String
+            }
+            '"' => {
+                lexer.bump();
+                while let Some(c) = lexer.peek().filter(|c| *c != '\n') {
+                    lexer.bump();
+                    match c {
+                        '\\' if lexer.peek() != Some('\n') => lexer.bump(),
+                        '"' => break,
+                        _ => (),
+                    }
+                }
+                Kind::String
+            }
+            c if c.is_alphanumeric() || matches!(c, '_' | '-' | '.') => {
+                lexer.bump_while(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '+'));
+                Kind::Name
+            }
+            c => {
+                lexer.bump();
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    _ => (),
+                }
+                Kind::Punct(c)
+            }
+        };
+
+        tokens.push(Token {
+            kind,
+            text: &text[start..lexer.offset],
+            line,
+            column,
+            end_line: lexer.line,
+            depth,
+        });
+    }
+
+    tokens
+}
+
+struct Lexer<'t> {
+    text: &'t str,
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'t> Lexer<'t> {
+    fn rest(&self) -> &'t str {
+        &self.text[self.offset..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) {
+        let Some(c) = self.peek() else {
+            return;
+        };
+        self.offset += c.len_utf8();
+        match c {
+            '\n' => {
+                self.line += 1;
+                self.column = 1;
+            }
+            '\r' | '\u{feff}' => (),
+            _ => self.column += 1,
+        }
+    }
+
+    fn bump_n(&mut self, n: usize) {
+        for _ in 0..n {
+            self.bump();
+        }
+    }
+
+    fn bump_while(&mut self, f: impl Fn(char) -> bool) {
+        while self.peek().is_some_and(&f) {
+            self.bump();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Comments;
+    use crate::sort::{sort, SortOptions};
+    use graphql_parser::parse_schema;
+    use graphql_printer::PrintOptions;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn keeps_comments_when_sorting() {
+        let source = indoc! {r#"
+            # Copyright Example Corp.
+            # Licensed under the MIT license.
+
+            # Everything about users
+            type User {
+              # TODO: split the name
+              name: String # display name
+              "The primary key"
+              # never reused
+              id: ID!
+              age: Int
+              # more fields to come
+            } # end of User
+
+            type Query {
+              user(
+                # defaults to the viewer
+                id: ID
+              ): User
+            }
+
+            # Trailing notes
+        "#};
+
+        let schema = parse_schema::<String>(source).unwrap();
+        let comments = Comments::extract(source, &schema);
+        let sorted = sort(&schema, &SortOptions::deep());
+
+        assert_eq!(
+            comments.print(&sorted, &PrintOptions::default()),
+            indoc! {r#"
+                # Copyright Example Corp.
+                # Licensed under the MIT license.
+
+                type Query {
+                  # defaults to the viewer
+                  user(id: ID): User
+                }
+
+                # Everything about users
+                type User {
+                  age: Int
+                  # never reused
+                  "The primary key"
+                  id: ID!
+                  # TODO: split the name
+                  name: String # display name
+                  # more fields to come
+                } # end of User
+
+                # Trailing notes
+            "#}
+        );
+    }
+
+    #[test]
+    fn keeps_comments_of_descriptions_enums_and_extensions() {
+        let source = indoc! {r#"
+            # How a user signs in
+            """
+            Authentication
+            methods
+            """
+            enum Auth {
+              PASSWORD
+              # deprecated soon
+              TOKEN
+            }
+
+            extend enum Auth {
+              SSO # single sign-on
+            }
+
+            directive @auth(
+              # the role required
+              role: String
+            ) on FIELD_DEFINITION
+        "#};
+
+        let schema = parse_schema::<String>(source).unwrap();
+        let comments = Comments::extract(source, &schema);
+
+        assert_eq!(
+            comments.print(&schema, &PrintOptions::default()),
+            indoc! {r#"
+                # How a user signs in
+                """
+                  Authentication
+                  methods
+                """
+                enum Auth {
+                  PASSWORD
+                  # deprecated soon
+                  TOKEN
+                }
+
+                extend enum Auth {
+                  SSO # single sign-on
+                }
+
+                # the role required
+                directive @auth(role: String) on FIELD_DEFINITION
+            "#}
+        );
+    }
+}
@@ -0,0 +1,356 @@
+use crate::prune::used_fields_by_operation;
+use crate::util;
+use graphql_parser::{
+    query::Document as QueryDoc,
+    schema::{Document as SchemaDoc, Field, TypeDefinition},
+};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+
+/// How many operations of a query corpus use each type and field of a schema.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Coverage {
+    /// The number of operations in the corpus.
+    pub operations: usize,
+    /// Every type of the schema, in definition order.
+    pub types: Vec<TypeCoverage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TypeCoverage {
+    pub name: String,
+    /// The number of operations selecting, returning or accepting the type.
+    pub operations: usize,
+    /// The fields of object and interface types, in definition order.
+    pub fields: Vec<FieldCoverage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldCoverage {
+    pub name: String,
+    /// The number of operations selecting the field, directly or through an
+    /// interface the type implements.
+    pub operations: usize,
+}
+
+impl Coverage {
+    /// Keeps only the types and fields no operation uses. Types that are used
+    /// but have unused fields are kept with just those fields.
+    pub fn unused(mut self) -> Self {
+        self.types.retain_mut(|t| {
+            t.fields.retain(|f| f.operations == 0);
+            t.operations == 0 || !t.fields.is_empty()
+        });
+        self
+    }
+}
+
+impl Display for Coverage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rows: Vec<(String, usize)> = self
+            .types
+            .iter()
+            .flat_map(|t| {
+                std::iter::once((t.name.clone(), t.operations)).chain(
+                    t.fields
+                        .iter()
+                        .map(|field| (format!("{}.{}", t.name, field.name), field.operations)),
+                )
+            })
+            .collect();
+        let width = rows
+            .iter()
+            .map(|(coordinate, _)| coordinate.len())
+            .chain(std::iter::once("COORDINATE".len()))
+            .max()
+            .unwrap_or_default();
+
+        writeln!(f, "{:<width$}  OPERATIONS", "COORDINATE")?;
+        for (coordinate, operations) in rows {
+            writeln!(f, "{coordinate:<width$}  {operations}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Counts the operations of the query document that use each type and field
+/// of the schema, using the same field tracking as [`prune`](crate::prune).
+/// Fields added by type extensions count towards the type they extend.
+pub fn coverage(schema_doc: &SchemaDoc<'_, String>, query_doc: &QueryDoc<'_, String>) -> Coverage {
+    let merged = util::merged_type_definitions(schema_doc);
+    let type_defs: Vec<&TypeDefinition<String>> = merged.iter().collect();
+    let type_map: HashMap<&str, &TypeDefinition<String>> = type_defs
+        .iter()
+        .map(|td| {
+            (
+                util::schema_type_definition_name(*td).unwrap().as_str(),
+                *td,
+            )
+        })
+        .collect();
+
+    let by_operation = used_fields_by_operation(schema_doc, query_doc);
+
+    let mut field_counts: HashMap<(&str, &str), usize> = HashMap::new();
+    let mut type_counts: HashMap<&str, usize> = HashMap::new();
+    for used_fields in &by_operation {
+        let mut used_types: HashSet<&str> = HashSet::new();
+
+        for td in &type_defs {
+            let name = util::schema_type_definition_name(*td).unwrap().as_str();
+            let interfaces = interfaces(td);
+            let selected = |field: &str| {
+                std::iter::once(name)
+                    .chain(interfaces.iter().map(String::as_str))
+                    .any(|t| used_fields.get(t).is_some_and(|set| set.contains(field)))
+            };
+
+            if used_fields.contains_key(name)
+                || interfaces.iter().any(|i| used_fields.contains_key(i))
+            {
+                used_types.insert(name);
+            }
+            for field in fields(td) {
+                if selected(&field.name) {
+                    *field_counts.entry((name, &field.name)).or_default() += 1;
+                    collect_field_types(field, &type_map, &mut used_types);
+                }
+            }
+        }
+
+        for name in used_types {
+            *type_counts.entry(name).or_default() += 1;
+        }
+    }
+
+    Coverage {
+        operations: by_operation.len(),
+        types: type_defs
+            .iter()
+            .map(|td| {
+                let name = util::schema_type_definition_name(*td).unwrap();
+                TypeCoverage {
+                    name: name.clone(),
+                    operations: type_counts.get(name.as_str()).copied().unwrap_or_default(),
+                    fields: fields(td)
+                        .iter()
+                        .map(|field| FieldCoverage {
+                            name: field.name.clone(),
+                            operations: field_counts
+                                .get(&(name.as_str(), field.name.as_str()))
+                                .copied()
+                                .unwrap_or_default(),
+                        })
+                        .collect(),
+                }
+            })
+            .collect(),
+    }
+}
+
+fn fields<'s, 'a>(td: &'s TypeDefinition<'a, String>) -> &'s [Field<'a, String>] {
+    match td {
+        TypeDefinition::Object(object) => &object.fields,
+        TypeDefinition::Interface(interface) => &interface.fields,
+        _ => &[],
+    }
+}
+
+fn interfaces<'s>(td: &'s TypeDefinition<'_, String>) -> &'s [String] {
+    match td {
+        TypeDefinition::Object(object) => &object.implements_interfaces,
+        TypeDefinition::Interface(interface) => &interface.implements_interfaces,
+        _ => &[],
+    }
+}
+
+/// Marks the type a used field returns and the input types of its arguments
+/// as used.
+fn collect_field_types<'s>(
+    field: &'s Field<'_, String>,
+    type_map: &HashMap<&str, &'s TypeDefinition<'_, String>>,
+    used_types: &mut HashSet<&'s str>,
+) {
+    used_types.insert(util::named_type(&field.field_type).unwrap());
+
+    let mut pending: Vec<&str> = field
+        .arguments
+        .iter()
+        .map(|arg| util::named_type(&arg.value_type).unwrap().as_str())
+        .collect();
+    while let Some(name) = pending.pop() {
+        if !used_types.insert(name) {
+            continue;
+        }
+        if let Some(TypeDefinition::InputObject(input)) = type_map.get(name) {
+            pending.extend(
+                input
+                    .fields
+                    .iter()
+                    .map(|f| util::named_type(&f.value_type).unwrap().as_str()),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::coverage;
+    use graphql_parser::{parse_query, parse_schema};
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    fn process(schema: &str, query: &str, unused: bool) -> String {
+        let schema = parse_schema::<String>(schema).unwrap();
+        let query = parse_query::<String>(query).unwrap();
+        let coverage = coverage(&schema, &query);
+        if unused {
+            coverage.unused().to_string()
+        } else {
+            coverage.to_string()
+        }
+    }
+
+    const SCHEMA: &str = indoc! {"
+        type Query {
+          user(filter: UserFilter): User
+          node: Node
+          version: String
+        }
+
+        interface Node {
+          id: ID!
+        }
+
+        type User implements Node {
+          id: ID!
+          name: String
+          email: String
+        }
+
+        input UserFilter {
+          role: Role
+        }
+
+        enum Role {
+          ADMIN
+        }
+
+        type Orphan {
+          id: ID!
+        }
+    "};
+
+    const QUERY: &str = indoc! {"
+        query A {
+          user {
+            name
+          }
+        }
+
+        query B {
+          user {
+            ...UserFields
+          }
+          node {
+            id
+          }
+        }
+
+        fragment UserFields on User {
+          name
+          email
+        }
+    "};
+
+    #[test]
+    fn counts_operations_per_field() {
+        assert_eq!(
+            process(SCHEMA, QUERY, false),
+            indoc! {"
+                COORDINATE     OPERATIONS
+                Query          2
+                Query.user     2
+                Query.node     1
+                Query.version  0
+                Node           1
+                Node.id        1
+                User           2
+                User.id        1
+                User.name      2
+                User.email     1
+                UserFilter     2
+                Role           2
+                Orphan         0
+                Orphan.id      0
+            "}
+        );
+    }
+
+    #[test]
+    fn lists_unused_types_and_fields() {
+        assert_eq!(
+            process(SCHEMA, QUERY, true),
+            indoc! {"
+                COORDINATE     OPERATIONS
+                Query          2
+                Query.version  0
+                Orphan         0
+                Orphan.id      0
+            "}
+        );
+    }
+
+    #[test]
+    fn counts_extension_fields() {
+        let schema = indoc! {"
+            type Query {
+              user: User
+            }
+
+            type User {
+              id: ID!
+            }
+
+            extend type User {
+              name: String
+              posts: [Post]
+            }
+
+            extend type Post {
+              title: String
+            }
+        "};
+        let query = indoc! {"
+            query A {
+              user {
+                name
+              }
+            }
+
+            query B {
+              user {
+                posts {
+                  title
+                }
+              }
+            }
+        "};
+
+        assert_eq!(
+            process(schema, query, false),
+            indoc! {"
+                COORDINATE  OPERATIONS
+                Query       2
+                Query.user  2
+                User        2
+                User.id     0
+                User.name   1
+                User.posts  1
+                Post        1
+                Post.title  1
+            "}
+        );
+    }
+}
@@ -0,0 +1,421 @@
+use crate::focus::{strip_unused_types, TypeGraph};
+use crate::util;
+use graphql_parser::schema::{
+    Definition, Document, InputObjectType, InputObjectTypeExtension, TypeDefinition, TypeExtension,
+    UnionType, UnionTypeExtension,
+};
+use graphql_printer::{print_schema, PrintOptions};
+use petgraph::visit::{Dfs, Reversed, Walker};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Display;
+
+/// Options for [`dependents`].
+#[derive(Debug, Clone, Default)]
+pub struct DependentsOptions {
+    /// The types to find the dependents of.
+    pub types: Vec<String>,
+}
+
+/// The types referencing a set of target types, and how the root operation
+/// types reach them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dependents<'a> {
+    /// The subset of the schema made up of the target types and every type
+    /// that references them, directly or not.
+    pub schema: Document<'a, String>,
+    /// For each root field leading to a target type, the shortest chain of
+    /// fields from it to the target as `Type.field` coordinates.
+    pub paths: Vec<Vec<String>>,
+}
+
+impl Dependents<'_> {
+    /// Prints the paths as comments, followed by the schema.
+    pub fn print(&self, options: &PrintOptions) -> String {
+        let mut out = String::new();
+        for path in &self.paths {
+            out.push_str(&format!("# {}\n", path.join(" -> ")));
+        }
+        if !self.paths.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&print_schema(&self.schema, options));
+        out
+    }
+}
+
+impl Display for Dependents<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.print(&PrintOptions::default()))
+    }
+}
+
+/// Walks the type graph built by [`focus`](crate::focus) backwards from the
+/// target types, returning every type that can reach them along with the
+/// field paths leading to them from the root operation types.
+pub fn dependents<'a>(
+    schema_ast: &Document<'a, String>,
+    options: &DependentsOptions,
+) -> Dependents<'a> {
+    let TypeGraph { graph, nodes, .. } = TypeGraph::new(schema_ast);
+    let reversed = Reversed(&graph);
+
+    let used: HashSet<&String> = options
+        .types
+        .iter()
+        .filter_map(|t| nodes.get(t))
+        .flat_map(|idx| {
+            Dfs::new(reversed, *idx)
+                .iter(reversed)
+                .map(|n| graph[n])
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    let targets: HashSet<&str> = options
+        .types
+        .iter()
+        .filter(|t| nodes.contains_key(t))
+        .map(String::as_str)
+        .collect();
+    let adjacency = output_edges(schema_ast);
+    let root_types = util::detect_root_types(schema_ast);
+    let mut roots = vec![
+        root_types.query,
+        root_types.mutation.unwrap_or("Mutation".to_string()),
+        root_types
+            .subscription
+            .unwrap_or("Subscription".to_string()),
+    ];
+    roots.dedup();
+
+    let mut paths = Vec::new();
+    for root in &roots {
+        for (next, coordinate) in adjacency.get(root.as_str()).into_iter().flatten() {
+            let Some(coordinate) = coordinate else {
+                continue;
+            };
+            if let Some(rest) = shortest_path(&adjacency, next, &targets) {
+                paths.push(std::iter::once(coordinate.clone()).chain(rest).collect());
+            }
+        }
+    }
+
+    Dependents {
+        schema: strip_unused_types(schema_ast, used),
+        paths,
+    }
+}
+
+/// The types each type leads to, their extensions included: the types of
+/// its fields, arguments and input fields, and the possible types of abstract
+/// types. Edges through fields, arguments and input fields carry their
+/// coordinate, while edges from an abstract type to its possible types carry
+/// none.
+fn output_edges<'s>(
+    schema_ast: &'s Document<'_, String>,
+) -> HashMap<&'s str, Vec<(&'s str, Option<String>)>> {
+    let mut edges: HashMap<&str, Vec<(&str, Option<String>)>> = HashMap::new();
+
+    for definition in &schema_ast.definitions {
+        let (name, fields, interfaces) = match definition {
+            Definition::TypeDefinition(TypeDefinition::Object(obj)) => {
+                (&obj.name, &obj.fields, &obj.implements_interfaces)
+            }
+            Definition::TypeDefinition(TypeDefinition::Interface(iface)) => {
+                (&iface.name, &iface.fields, &iface.implements_interfaces)
+            }
+            Definition::TypeExtension(TypeExtension::Object(obj)) => {
+                (&obj.name, &obj.fields, &obj.implements_interfaces)
+            }
+            Definition::TypeExtension(TypeExtension::Interface(iface)) => {
+                (&iface.name, &iface.fields, &iface.implements_interfaces)
+            }
+            Definition::TypeDefinition(TypeDefinition::Union(UnionType {
+                name, types, ..
+            }))
+            | Definition::TypeExtension(TypeExtension::Union(UnionTypeExtension {
+                name,
+                types,
+                ..
+            })) => {
+                edges
+                    .entry(name)
+                    .or_default()
+                    .extend(types.iter().map(|t| (t.as_str(), None)));
+                continue;
+            }
+            Definition::TypeDefinition(TypeDefinition::InputObject(InputObjectType {
+                name,
+                fields,
+                ..
+            }))
+            | Definition::TypeExtension(TypeExtension::InputObject(InputObjectTypeExtension {
+                name,
+                fields,
+                ..
+            })) => {
+                edges
+                    .entry(name)
+                    .or_default()
+                    .extend(fields.iter().map(|field| {
+                        (
+                            util::named_type(&field.value_type).unwrap().as_str(),
+                            Some(format!("{name}.{}", field.name)),
+                        )
+                    }));
+                continue;
+            }
+            _ => continue,
+        };
+
+        let type_edges = edges.entry(name).or_default();
+        for field in fields {
+            type_edges.push((
+                util::named_type(&field.field_type).unwrap().as_str(),
+                Some(format!("{name}.{}", field.name)),
+            ));
+            type_edges.extend(field.arguments.iter().map(|arg| {
+                (
+                    util::named_type(&arg.value_type).unwrap().as_str(),
+                    Some(format!("{name}.{}({}:)", field.name, arg.name)),
+                )
+            }));
+        }
+        for iface in interfaces {
+            edges.entry(iface).or_default().push((name, None));
+        }
+    }
+
+    edges
+}
+
+/// Finds the shortest chain of field coordinates leading from `start` to
+/// one of the targets, which is empty when `start` is a target.
+fn shortest_path<'s>(
+    adjacency: &'s HashMap<&'s str, Vec<(&'s str, Option<String>)>>,
+    start: &'s str,
+    targets: &HashSet<&str>,
+) -> Option<Vec<String>> {
+    let mut previous: HashMap<&str, (&str, Option<&String>)> = HashMap::new();
+    let mut seen = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(name) = queue.pop_front() {
+        if targets.contains(name) {
+            let mut path = Vec::new();
+            let mut current = name;
+            while let Some((prev, coordinate)) = previous.get(current) {
+                path.extend(coordinate.cloned());
+                current = prev;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        for (next, coordinate) in adjacency.get(name).into_iter().flatten() {
+            if seen.insert(next) {
+                previous.insert(next, (name, coordinate.as_ref()));
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dependents, DependentsOptions};
+    use graphql_parser::parse_schema;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn finds_dependents_and_paths() {
+        let schema = parse_schema::<String>(indoc! {"
+            type Query {
+              viewer: User
+              node(id: ID!): Node
+              version: String
+            }
+
+            type Mutation {
+              addPaymentMethod(number: String!): PaymentMethod
+            }
+
+            interface Node {
+              id: ID!
+            }
+
+            type User implements Node {
+              id: ID!
+              wallet: Wallet
+            }
+
+            type Wallet {
+              methods: [PaymentMethod]
+            }
+
+            type PaymentMethod {
+              last4: String
+            }
+
+            type Product implements Node {
+              id: ID!
+              name: String
+            }
+        "})
+        .unwrap();
+
+        let options = DependentsOptions {
+            types: vec!["PaymentMethod".to_string()],
+        };
+
+        assert_eq!(
+            dependents(&schema, &options).to_string(),
+            indoc! {"
+                # Query.viewer -> User.wallet -> Wallet.methods
+                # Query.node -> User.wallet -> Wallet.methods
+                # Mutation.addPaymentMethod
+
+                type Query {
+                  viewer: User
+                  node(id: ID!): Node
+                  version: String
+                }
+
+                type Mutation {
+                  addPaymentMethod(number: String!): PaymentMethod
+                }
+
+                interface Node {
+                  id: ID!
+                }
+
+                type User implements Node {
+                  id: ID!
+                  wallet: Wallet
+                }
+
+                type Wallet {
+                  methods: [PaymentMethod]
+                }
+
+                type PaymentMethod {
+                  last4: String
+                }
+            "}
+        );
+    }
+
+    #[test]
+    fn follows_root_type_extensions() {
+        let schema = parse_schema::<String>(indoc! {"
+            type Query {
+              version: String
+            }
+
+            extend type Query {
+              wallet: Wallet
+            }
+
+            type Wallet {
+              methods: [PaymentMethod]
+            }
+
+            type PaymentMethod {
+              last4: String
+            }
+        "})
+        .unwrap();
+
+        let options = DependentsOptions {
+            types: vec!["PaymentMethod".to_string()],
+        };
+
+        assert_eq!(
+            dependents(&schema, &options).to_string(),
+            indoc! {"
+                # Query.wallet -> Wallet.methods
+
+                type Query {
+                  version: String
+                }
+
+                extend type Query {
+                  wallet: Wallet
+                }
+
+                type Wallet {
+                  methods: [PaymentMethod]
+                }
+
+                type PaymentMethod {
+                  last4: String
+                }
+            "}
+        );
+    }
+
+    #[test]
+    fn follows_arguments_and_input_fields() {
+        let schema = parse_schema::<String>(indoc! {"
+            type Query {
+              users(filter: UserFilter): [User]
+            }
+
+            type Mutation {
+              setRole(id: ID!, role: Role!): User
+            }
+
+            type User {
+              name: String
+            }
+
+            input UserFilter {
+              name: String
+            }
+
+            extend input UserFilter {
+              role: Role
+            }
+
+            enum Role {
+              ADMIN
+            }
+        "})
+        .unwrap();
+
+        let options = DependentsOptions {
+            types: vec!["Role".to_string()],
+        };
+
+        assert_eq!(
+            dependents(&schema, &options).to_string(),
+            indoc! {"
+                # Query.users(filter:) -> UserFilter.role
+                # Mutation.setRole(role:)
+
+                type Query {
+                  users(filter: UserFilter): [User]
+                }
+
+                type Mutation {
+                  setRole(id: ID!, role: Role!): User
+                }
+
+                input UserFilter {
+                  name: String
+                }
+
+                extend input UserFilter {
+                  role: Role
+                }
+
+                enum Role {
+                  ADMIN
+                }
+            "}
+        );
+    }
+}
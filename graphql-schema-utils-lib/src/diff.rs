@@ -0,0 +1,876 @@
+use crate::util;
+use graphql_parser::schema::{
+    Definition, Directive, DirectiveDefinition, Document, EnumValue, Field, InputValue, Type,
+    TypeDefinition,
+};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// How a change affects existing clients of the schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Criticality {
+    /// Existing operations may stop validating or executing.
+    Breaking,
+    /// Existing operations keep working but may observe new behavior, such as
+    /// an enum value or union member they do not handle.
+    Dangerous,
+    /// Existing operations are unaffected.
+    Safe,
+}
+
+impl Display for Criticality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Criticality::Breaking => f.pad("BREAKING"),
+            Criticality::Dangerous => f.pad("DANGEROUS"),
+            Criticality::Safe => f.pad("SAFE"),
+        }
+    }
+}
+
+/// A single difference between two schemas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub criticality: Criticality,
+    /// The schema coordinate the change applies to, such as `User`,
+    /// `User.email` or `User.posts(first:)`.
+    pub path: String,
+    pub message: String,
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<9}  {}", self.criticality, self.message)
+    }
+}
+
+/// Compares two schemas and reports the added, removed and changed types,
+/// fields, arguments, enum values, union members, interface implementations
+/// and directives. Type extensions are compared as part of the types they
+/// extend. Changes to existing types are listed in the order of the old
+/// schema, followed by additions.
+pub fn diff(old: &Document<'_, String>, new: &Document<'_, String>) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    diff_schema_definitions(old, new, &mut changes);
+
+    let (old_merged, new_merged) = (
+        util::merged_type_definitions(old),
+        util::merged_type_definitions(new),
+    );
+    let old_types = type_definitions(&old_merged);
+    let new_types = type_definitions(&new_merged);
+    let new_by_name: HashMap<_, _> = new_types.iter().copied().collect();
+    let old_by_name: HashMap<_, _> = old_types.iter().copied().collect();
+
+    for (name, old_type) in &old_types {
+        match new_by_name.get(name) {
+            Some(new_type) => diff_type(old_type, new_type, &mut changes),
+            None => changes.push(Change {
+                criticality: Criticality::Breaking,
+                path: name.to_string(),
+                message: format!("Type `{name}` was removed"),
+            }),
+        }
+    }
+    for (name, _) in &new_types {
+        if !old_by_name.contains_key(name) {
+            changes.push(Change {
+                criticality: Criticality::Safe,
+                path: name.to_string(),
+                message: format!("Type `{name}` was added"),
+            });
+        }
+    }
+
+    let old_directives = directive_definitions(old);
+    let new_directives = directive_definitions(new);
+
+    for old_directive in &old_directives {
+        match new_directives.iter().find(|d| d.name == old_directive.name) {
+            Some(new_directive) => diff_directive(old_directive, new_directive, &mut changes),
+            None => changes.push(Change {
+                criticality: Criticality::Breaking,
+                path: format!("@{}", old_directive.name),
+                message: format!("Directive `@{}` was removed", old_directive.name),
+            }),
+        }
+    }
+    for new_directive in &new_directives {
+        if !old_directives.iter().any(|d| d.name == new_directive.name) {
+            changes.push(Change {
+                criticality: Criticality::Safe,
+                path: format!("@{}", new_directive.name),
+                message: format!("Directive `@{}` was added", new_directive.name),
+            });
+        }
+    }
+
+    changes
+}
+
+fn type_definitions<'d, 'a>(
+    types: &'d [TypeDefinition<'a, String>],
+) -> Vec<(&'d String, &'d TypeDefinition<'a, String>)> {
+    types
+        .iter()
+        .filter_map(|td| util::schema_type_definition_name(td).map(|name| (name, td)))
+        .collect()
+}
+
+fn directive_definitions<'d, 'a>(
+    doc: &'d Document<'a, String>,
+) -> Vec<&'d DirectiveDefinition<'a, String>> {
+    doc.definitions
+        .iter()
+        .filter_map(|def| match def {
+            Definition::DirectiveDefinition(directive) => Some(directive),
+            _ => None,
+        })
+        .collect()
+}
+
+fn diff_schema_definitions(
+    old: &Document<'_, String>,
+    new: &Document<'_, String>,
+    changes: &mut Vec<Change>,
+) {
+    let (old_roots, new_roots) = (root_types(old), root_types(new));
+
+    for ((operation, old), new) in ["query", "mutation", "subscription"]
+        .iter()
+        .zip(old_roots)
+        .zip(new_roots)
+    {
+        if let Some(old) = old.filter(|old| Some(old) != new.as_ref()) {
+            changes.push(Change {
+                criticality: Criticality::Breaking,
+                path: String::from("schema"),
+                message: format!(
+                    "Schema {operation} root changed from `{old}` to {}",
+                    new.map_or_else(|| String::from("none"), |new| format!("`{new}`"))
+                ),
+            });
+        }
+    }
+}
+
+/// The query, mutation and subscription root types. Without a schema
+/// definition the conventionally named types are used, if they exist.
+fn root_types(doc: &Document<'_, String>) -> [Option<String>; 3] {
+    let schema_definition = doc.definitions.iter().find_map(|def| match def {
+        Definition::SchemaDefinition(schema_definition) => Some(schema_definition),
+        _ => None,
+    });
+
+    match schema_definition {
+        Some(schema) => [
+            schema.query.clone(),
+            schema.mutation.clone(),
+            schema.subscription.clone(),
+        ],
+        None => ["Query", "Mutation", "Subscription"].map(|name| {
+            type_definitions(&util::merged_type_definitions(doc))
+                .iter()
+                .any(|(n, _)| *n == name)
+                .then(|| name.to_string())
+        }),
+    }
+}
+
+fn diff_type(
+    old: &TypeDefinition<'_, String>,
+    new: &TypeDefinition<'_, String>,
+    changes: &mut Vec<Change>,
+) {
+    match (old, new) {
+        (TypeDefinition::Scalar(_), TypeDefinition::Scalar(_)) => (),
+        (TypeDefinition::Object(old), TypeDefinition::Object(new)) => {
+            diff_interfaces(
+                &old.name,
+                &old.implements_interfaces,
+                &new.implements_interfaces,
+                changes,
+            );
+            diff_fields(&old.name, &old.fields, &new.fields, changes);
+        }
+        (TypeDefinition::Interface(old), TypeDefinition::Interface(new)) => {
+            diff_interfaces(
+                &old.name,
+                &old.implements_interfaces,
+                &new.implements_interfaces,
+                changes,
+            );
+            diff_fields(&old.name, &old.fields, &new.fields, changes);
+        }
+        (TypeDefinition::Union(old), TypeDefinition::Union(new)) => {
+            for member in old.types.iter().filter(|t| !new.types.contains(t)) {
+                changes.push(Change {
+                    criticality: Criticality::Breaking,
+                    path: old.name.clone(),
+                    message: format!("Member `{member}` was removed from union `{}`", old.name),
+                });
+            }
+            for member in new.types.iter().filter(|t| !old.types.contains(t)) {
+                changes.push(Change {
+                    criticality: Criticality::Dangerous,
+                    path: old.name.clone(),
+                    message: format!("Member `{member}` was added to union `{}`", old.name),
+                });
+            }
+        }
+        (TypeDefinition::Enum(old), TypeDefinition::Enum(new)) => {
+            diff_enum_values(&old.name, &old.values, &new.values, changes);
+        }
+        (TypeDefinition::InputObject(old), TypeDefinition::InputObject(new)) => {
+            diff_input_values(&old.name, "Input field", &old.fields, &new.fields, changes);
+        }
+        _ => {
+            let name = util::schema_type_definition_name(old).unwrap();
+            changes.push(Change {
+                criticality: Criticality::Breaking,
+                path: name.clone(),
+                message: format!(
+                    "Type `{name}` changed kind from {} to {}",
+                    type_kind(old),
+                    type_kind(new)
+                ),
+            });
+        }
+    }
+}
+
+fn type_kind(td: &TypeDefinition<'_, String>) -> &'static str {
+    match td {
+        TypeDefinition::Scalar(_) => "scalar",
+        TypeDefinition::Object(_) => "object",
+        TypeDefinition::Interface(_) => "interface",
+        TypeDefinition::Union(_) => "union",
+        TypeDefinition::Enum(_) => "enum",
+        TypeDefinition::InputObject(_) => "input object",
+    }
+}
+
+fn diff_interfaces(type_name: &str, old: &[String], new: &[String], changes: &mut Vec<Change>) {
+    for interface in old.iter().filter(|i| !new.contains(i)) {
+        changes.push(Change {
+            criticality: Criticality::Breaking,
+            path: type_name.to_string(),
+            message: format!("`{type_name}` no longer implements interface `{interface}`"),
+        });
+    }
+    for interface in new.iter().filter(|i| !old.contains(i)) {
+        changes.push(Change {
+            criticality: Criticality::Dangerous,
+            path: type_name.to_string(),
+            message: format!("`{type_name}` now implements interface `{interface}`"),
+        });
+    }
+}
+
+fn diff_fields(
+    type_name: &str,
+    old: &[Field<'_, String>],
+    new: &[Field<'_, String>],
+    changes: &mut Vec<Change>,
+) {
+    for old_field in old {
+        let path = format!("{type_name}.{}", old_field.name);
+        let Some(new_field) = new.iter().find(|f| f.name == old_field.name) else {
+            changes.push(Change {
+                criticality: Criticality::Breaking,
+                message: format!("Field `{path}` was removed"),
+                path,
+            });
+            continue;
+        };
+
+        if !same_type(&old_field.field_type, &new_field.field_type) {
+            let criticality = if is_safe_output_change(&old_field.field_type, &new_field.field_type)
+            {
+                Criticality::Safe
+            } else {
+                Criticality::Breaking
+            };
+            changes.push(Change {
+                criticality,
+                path: path.clone(),
+                message: format!(
+                    "Field `{path}` changed type from `{}` to `{}`",
+                    old_field.field_type, new_field.field_type
+                ),
+            });
+        }
+
+        diff_deprecation(
+            &path,
+            "Field",
+            is_deprecated(&old_field.directives),
+            is_deprecated(&new_field.directives),
+            changes,
+        );
+        diff_arguments(&path, &old_field.arguments, &new_field.arguments, changes);
+    }
+
+    for new_field in new.iter().filter(|f| !old.iter().any(|o| o.name == f.name)) {
+        let path = format!("{type_name}.{}", new_field.name);
+        changes.push(Change {
+            criticality: Criticality::Safe,
+            message: format!("Field `{path}` was added"),
+            path,
+        });
+    }
+}
+
+fn diff_arguments(
+    field_path: &str,
+    old: &[InputValue<'_, String>],
+    new: &[InputValue<'_, String>],
+    changes: &mut Vec<Change>,
+) {
+    for old_arg in old {
+        let path = format!("{field_path}({}:)", old_arg.name);
+        let Some(new_arg) = new.iter().find(|a| a.name == old_arg.name) else {
+            changes.push(Change {
+                criticality: Criticality::Breaking,
+                message: format!(
+                    "Argument `{}` was removed from `{field_path}`",
+                    old_arg.name
+                ),
+                path,
+            });
+            continue;
+        };
+        diff_input_value(&path, "Argument", old_arg, new_arg, changes);
+    }
+
+    for new_arg in new.iter().filter(|a| !old.iter().any(|o| o.name == a.name)) {
+        let (criticality, required) = if is_required(new_arg) {
+            (Criticality::Breaking, "Required")
+        } else {
+            (Criticality::Safe, "Optional")
+        };
+        changes.push(Change {
+            criticality,
+            path: format!("{field_path}({}:)", new_arg.name),
+            message: format!(
+                "{required} argument `{}` was added to `{field_path}`",
+                new_arg.name
+            ),
+        });
+    }
+}
+
+fn diff_input_values(
+    type_name: &str,
+    label: &str,
+    old: &[InputValue<'_, String>],
+    new: &[InputValue<'_, String>],
+    changes: &mut Vec<Change>,
+) {
+    for old_value in old {
+        let path = format!("{type_name}.{}", old_value.name);
+        let Some(new_value) = new.iter().find(|v| v.name == old_value.name) else {
+            changes.push(Change {
+                criticality: Criticality::Breaking,
+                message: format!("{label} `{path}` was removed"),
+                path,
+            });
+            continue;
+        };
+        diff_input_value(&path, label, old_value, new_value, changes);
+    }
+
+    for new_value in new.iter().filter(|v| !old.iter().any(|o| o.name == v.name)) {
+        let path = format!("{type_name}.{}", new_value.name);
+        let (criticality, required) = if is_required(new_value) {
+            (Criticality::Breaking, "Required")
+        } else {
+            (Criticality::Safe, "Optional")
+        };
+        changes.push(Change {
+            criticality,
+            message: format!("{required} {} `{path}` was added", label.to_lowercase()),
+            path,
+        });
+    }
+}
+
+/// Compares an argument or input field that exists in both schemas.
+fn diff_input_value(
+    path: &str,
+    label: &str,
+    old: &InputValue<'_, String>,
+    new: &InputValue<'_, String>,
+    changes: &mut Vec<Change>,
+) {
+    if !same_type(&old.value_type, &new.value_type) {
+        let criticality = if is_safe_input_change(&old.value_type, &new.value_type) {
+            Criticality::Safe
+        } else {
+            Criticality::Breaking
+        };
+        changes.push(Change {
+            criticality,
+            path: path.to_string(),
+            message: format!(
+                "{label} `{path}` changed type from `{}` to `{}`",
+                old.value_type, new.value_type
+            ),
+        });
+    }
+
+    let old_default = old.default_value.as_ref().map(|v| format!("`{v}`"));
+    let new_default = new.default_value.as_ref().map(|v| format!("`{v}`"));
+    if old_default != new_default {
+        changes.push(Change {
+            criticality: Criticality::Dangerous,
+            path: path.to_string(),
+            message: format!(
+                "{label} `{path}` changed default value from {} to {}",
+                old_default.as_deref().unwrap_or("none"),
+                new_default.as_deref().unwrap_or("none")
+            ),
+        });
+    }
+
+    diff_deprecation(
+        path,
+        label,
+        is_deprecated(&old.directives),
+        is_deprecated(&new.directives),
+        changes,
+    );
+}
+
+fn diff_enum_values(
+    type_name: &str,
+    old: &[EnumValue<'_, String>],
+    new: &[EnumValue<'_, String>],
+    changes: &mut Vec<Change>,
+) {
+    for old_value in old {
+        let path = format!("{type_name}.{}", old_value.name);
+        match new.iter().find(|v| v.name == old_value.name) {
+            Some(new_value) => diff_deprecation(
+                &path,
+                "Enum value",
+                is_deprecated(&old_value.directives),
+                is_deprecated(&new_value.directives),
+                changes,
+            ),
+            None => changes.push(Change {
+                criticality: Criticality::Breaking,
+                message: format!("Enum value `{path}` was removed"),
+                path,
+            }),
+        }
+    }
+
+    for new_value in new.iter().filter(|v| !old.iter().any(|o| o.name == v.name)) {
+        let path = format!("{type_name}.{}", new_value.name);
+        changes.push(Change {
+            criticality: Criticality::Dangerous,
+            message: format!("Enum value `{path}` was added"),
+            path,
+        });
+    }
+}
+
+fn diff_directive(
+    old: &DirectiveDefinition<'_, String>,
+    new: &DirectiveDefinition<'_, String>,
+    changes: &mut Vec<Change>,
+) {
+    let path = format!("@{}", old.name);
+
+    for location in old.locations.iter().filter(|l| !new.locations.contains(l)) {
+        changes.push(Change {
+            criticality: Criticality::Breaking,
+            path: path.clone(),
+            message: format!(
+                "Location `{}` was removed from directive `{path}`",
+                location.as_str()
+            ),
+        });
+    }
+    for location in new.locations.iter().filter(|l| !old.locations.contains(l)) {
+        changes.push(Change {
+            criticality: Criticality::Safe,
+            path: path.clone(),
+            message: format!(
+                "Location `{}` was added to directive `{path}`",
+                location.as_str()
+            ),
+        });
+    }
+
+    if old.repeatable && !new.repeatable {
+        changes.push(Change {
+            criticality: Criticality::Breaking,
+            path: path.clone(),
+            message: format!("Directive `{path}` is no longer repeatable"),
+        });
+    }
+
+    diff_arguments(&path, &old.arguments, &new.arguments, changes);
+}
+
+fn diff_deprecation(path: &str, label: &str, old: bool, new: bool, changes: &mut Vec<Change>) {
+    let message = match (old, new) {
+        (false, true) => format!("{label} `{path}` was deprecated"),
+        (true, false) => format!("{label} `{path}` is no longer deprecated"),
+        _ => return,
+    };
+    changes.push(Change {
+        criticality: Criticality::Safe,
+        path: path.to_string(),
+        message,
+    });
+}
+
+fn is_deprecated(directives: &[Directive<'_, String>]) -> bool {
+    directives.iter().any(|d| d.name == "deprecated")
+}
+
+/// An argument or input field clients must now provide.
+fn is_required(value: &InputValue<'_, String>) -> bool {
+    matches!(value.value_type, Type::NonNullType(_)) && value.default_value.is_none()
+}
+
+/// Structural equality for types that may come from documents with different
+/// lifetimes.
+fn same_type(old: &Type<'_, String>, new: &Type<'_, String>) -> bool {
+    match (old, new) {
+        (Type::NamedType(old), Type::NamedType(new)) => old == new,
+        (Type::ListType(old), Type::ListType(new)) => same_type(old, new),
+        (Type::NonNullType(old), Type::NonNullType(new)) => same_type(old, new),
+        _ => false,
+    }
+}
+
+/// Output types may only become stricter: adding non-null is safe since
+/// clients already handle the value being present.
+fn is_safe_output_change(old: &Type<'_, String>, new: &Type<'_, String>) -> bool {
+    match (old, new) {
+        (Type::NamedType(old), Type::NamedType(new)) => old == new,
+        (Type::ListType(old), Type::ListType(new)) => is_safe_output_change(old, new),
+        (Type::NonNullType(old), Type::NonNullType(new)) => is_safe_output_change(old, new),
+        (old, Type::NonNullType(new)) => is_safe_output_change(old, new),
+        _ => false,
+    }
+}
+
+/// Input types may only become looser: dropping non-null is safe since
+/// clients that send a value keep working.
+fn is_safe_input_change(old: &Type<'_, String>, new: &Type<'_, String>) -> bool {
+    match (old, new) {
+        (Type::NamedType(old), Type::NamedType(new)) => old == new,
+        (Type::ListType(old), Type::ListType(new)) => is_safe_input_change(old, new),
+        (Type::NonNullType(old), Type::NonNullType(new)) => is_safe_input_change(old, new),
+        (Type::NonNullType(old), new) => is_safe_input_change(old, new),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff, Criticality};
+    use graphql_parser::parse_schema;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    fn process(old: &str, new: &str) -> Vec<(Criticality, String)> {
+        let old = parse_schema::<String>(old).unwrap();
+        let new = parse_schema::<String>(new).unwrap();
+        diff(&old, &new)
+            .into_iter()
+            .map(|c| (c.criticality, c.message))
+            .collect()
+    }
+
+    #[test]
+    fn identical_schemas_have_no_changes() {
+        let schema = indoc! {"
+            type Query {
+              user(id: ID!): User
+            }
+
+            type User {
+              id: ID!
+            }
+        "};
+
+        assert_eq!(process(schema, schema), vec![]);
+    }
+
+    #[test]
+    fn classifies_field_changes() {
+        let old = indoc! {"
+            type Query {
+              user(id: ID!, first: Int = 10): User
+            }
+
+            type User {
+              id: ID
+              email: String
+              name: String
+            }
+        "};
+        let new = indoc! {"
+            type Query {
+              user(id: ID, first: Int = 20, filter: String!): User
+            }
+
+            type User {
+              id: ID!
+              name: Int
+              age: Int @deprecated
+            }
+        "};
+
+        assert_eq!(
+            process(old, new),
+            vec![
+                (
+                    Criticality::Safe,
+                    String::from("Argument `Query.user(id:)` changed type from `ID!` to `ID`")
+                ),
+                (
+                    Criticality::Dangerous,
+                    String::from(
+                        "Argument `Query.user(first:)` changed default value from `10` to `20`"
+                    )
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Required argument `filter` was added to `Query.user`")
+                ),
+                (
+                    Criticality::Safe,
+                    String::from("Field `User.id` changed type from `ID` to `ID!`")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Field `User.email` was removed")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Field `User.name` changed type from `String` to `Int`")
+                ),
+                (
+                    Criticality::Safe,
+                    String::from("Field `User.age` was added")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn compares_type_extensions_with_their_types() {
+        let old = indoc! {"
+            type Query {
+              a: Int
+            }
+
+            extend type Query {
+              b: Int
+            }
+
+            extend enum Color {
+              RED
+            }
+        "};
+        let new = indoc! {"
+            type Query {
+              a: Int
+              b: Int
+            }
+
+            extend type Query {
+              c: Int
+            }
+
+            enum Color {
+              RED
+              GREEN
+            }
+        "};
+
+        assert_eq!(
+            process(old, new),
+            vec![
+                (Criticality::Safe, String::from("Field `Query.c` was added")),
+                (
+                    Criticality::Dangerous,
+                    String::from("Enum value `Color.GREEN` was added")
+                ),
+            ]
+        );
+        assert_eq!(
+            process(old, "type Query { a: Int }"),
+            vec![
+                (
+                    Criticality::Breaking,
+                    String::from("Field `Query.b` was removed")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Type `Color` was removed")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn classifies_type_changes() {
+        let old = indoc! {"
+            interface Node {
+              id: ID!
+            }
+
+            type User implements Node {
+              id: ID!
+            }
+
+            type Post {
+              id: ID!
+            }
+
+            union Result = User | Post
+
+            enum Role {
+              ADMIN
+              USER
+            }
+
+            input Filter {
+              name: String
+            }
+
+            scalar Date
+        "};
+        let new = indoc! {"
+            interface Node {
+              id: ID!
+            }
+
+            type User {
+              id: ID!
+            }
+
+            type Post implements Node {
+              id: ID!
+            }
+
+            union Result = User
+
+            enum Role {
+              USER
+              GUEST
+            }
+
+            input Filter {
+              name: String
+              limit: Int!
+            }
+
+            enum Date {
+              TODAY
+            }
+
+            type Comment {
+              id: ID!
+            }
+        "};
+
+        assert_eq!(
+            process(old, new),
+            vec![
+                (
+                    Criticality::Breaking,
+                    String::from("`User` no longer implements interface `Node`")
+                ),
+                (
+                    Criticality::Dangerous,
+                    String::from("`Post` now implements interface `Node`")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Member `Post` was removed from union `Result`")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Enum value `Role.ADMIN` was removed")
+                ),
+                (
+                    Criticality::Dangerous,
+                    String::from("Enum value `Role.GUEST` was added")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Required input field `Filter.limit` was added")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Type `Date` changed kind from scalar to enum")
+                ),
+                (Criticality::Safe, String::from("Type `Comment` was added")),
+            ]
+        );
+    }
+
+    #[test]
+    fn classifies_directive_and_root_changes() {
+        let old = indoc! {"
+            schema {
+              query: Query
+            }
+
+            directive @auth(role: String) on FIELD_DEFINITION | OBJECT
+
+            directive @internal on FIELD_DEFINITION
+
+            type Query {
+              id: ID
+            }
+        "};
+        let new = indoc! {"
+            schema {
+              query: RootQuery
+            }
+
+            directive @auth(role: String) on FIELD_DEFINITION
+
+            type RootQuery {
+              id: ID
+            }
+        "};
+
+        assert_eq!(
+            process(old, new),
+            vec![
+                (
+                    Criticality::Breaking,
+                    String::from("Schema query root changed from `Query` to `RootQuery`")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Type `Query` was removed")
+                ),
+                (
+                    Criticality::Safe,
+                    String::from("Type `RootQuery` was added")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Location `OBJECT` was removed from directive `@auth`")
+                ),
+                (
+                    Criticality::Breaking,
+                    String::from("Directive `@internal` was removed")
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,1402 @@
+use crate::util;
+use graphql_parser::schema::{
+    Definition, Directive, Document, EnumValue, Field, InputObjectType, InputObjectTypeExtension,
+    InputValue, InterfaceType, InterfaceTypeExtension, ObjectType, ObjectTypeExtension, ScalarType,
+    TypeDefinition, TypeExtension, UnionType, UnionTypeExtension,
+};
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Walker;
+use std::collections::{hash_map::Entry, HashMap, HashSet, VecDeque};
+
+/// Options for [`focus`].
+#[derive(Debug, Clone, Default)]
+pub struct FocusOptions {
+    /// The types to focus on. Every type reachable from them is retained.
+    pub types: Vec<String>,
+    /// How many references to follow from the focused types. When unset,
+    /// every reachable type is retained.
+    pub depth: Option<usize>,
+    /// What happens to the types at the maximum depth.
+    pub boundary: Boundary,
+}
+
+/// How the types at the maximum depth of a focus are output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Boundary {
+    /// Fields referring to types past the maximum depth are removed.
+    #[default]
+    Trim,
+    /// Fields are kept, and the types past the maximum depth they refer to
+    /// are replaced by scalars of the same name.
+    Stub,
+}
+
+/// What an edge of a [`TypeGraph`] stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EdgeKind {
+    Field,
+    /// From a type to the type of an argument of one of its fields, or from
+    /// a directive to the type of one of its arguments.
+    Argument,
+    /// From an interface to a type implementing it.
+    Implements,
+    UnionMember,
+    InputField,
+    /// From a type to a directive applied to it or to its fields, values or
+    /// arguments.
+    Directive,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TypeEdge<'s> {
+    pub kind: EdgeKind,
+    /// The field, input field or argument the edge goes through.
+    pub field: Option<&'s String>,
+}
+
+impl<'s> TypeEdge<'s> {
+    fn kind(kind: EdgeKind) -> Self {
+        TypeEdge { kind, field: None }
+    }
+
+    fn through(kind: EdgeKind, name: &'s String) -> Self {
+        TypeEdge {
+            kind,
+            field: Some(name),
+        }
+    }
+}
+
+/// The types of a schema as a graph, with an edge from each type to the
+/// types its fields, arguments and input fields return, the members of a
+/// union and the types implementing an interface. Extensions add their edges
+/// to the type they extend. Directive definitions are nodes too, reached
+/// from the types using them and leading to their argument types.
+pub(crate) struct TypeGraph<'s> {
+    pub graph: petgraph::Graph<&'s String, TypeEdge<'s>>,
+    pub nodes: HashMap<&'s String, NodeIndex>,
+    pub directives: HashMap<&'s String, NodeIndex>,
+}
+
+impl<'s> TypeGraph<'s> {
+    pub fn new(schema_ast: &'s Document<'_, String>) -> Self {
+        let mut graph = TypeGraph {
+            graph: petgraph::Graph::new(),
+            nodes: HashMap::new(),
+            directives: HashMap::new(),
+        };
+
+        for definition in &schema_ast.definitions {
+            if let Definition::DirectiveDefinition(directive) = definition {
+                let idx = graph.graph.add_node(&directive.name);
+                graph.directives.insert(&directive.name, idx);
+            }
+        }
+
+        for definition in &schema_ast.definitions {
+            match definition {
+                Definition::SchemaDefinition(_schema_definition) => (),
+                Definition::TypeDefinition(type_definition) => match type_definition {
+                    TypeDefinition::Scalar(scalar_type) => {
+                        let idx = graph.node(&scalar_type.name);
+                        graph.add_directives(idx, &scalar_type.directives);
+                    }
+                    TypeDefinition::Object(object_type) => {
+                        let idx = graph.node(&object_type.name);
+                        graph.add_directives(idx, &object_type.directives);
+                        graph.add_fields(idx, &object_type.fields);
+                        graph.add_interfaces(idx, &object_type.implements_interfaces);
+                    }
+                    TypeDefinition::Interface(interface_type) => {
+                        let idx = graph.node(&interface_type.name);
+                        graph.add_directives(idx, &interface_type.directives);
+                        graph.add_fields(idx, &interface_type.fields);
+                        graph.add_interfaces(idx, &interface_type.implements_interfaces);
+                    }
+                    TypeDefinition::Union(union_type) => {
+                        let idx = graph.node(&union_type.name);
+                        graph.add_directives(idx, &union_type.directives);
+                        graph.add_members(idx, &union_type.types);
+                    }
+                    TypeDefinition::Enum(enum_type) => {
+                        let idx = graph.node(&enum_type.name);
+                        graph.add_directives(idx, &enum_type.directives);
+                        for value in &enum_type.values {
+                            graph.add_directives(idx, &value.directives);
+                        }
+                    }
+                    TypeDefinition::InputObject(input_object_type) => {
+                        let idx = graph.node(&input_object_type.name);
+                        graph.add_directives(idx, &input_object_type.directives);
+                        graph.add_input_values(
+                            idx,
+                            EdgeKind::InputField,
+                            &input_object_type.fields,
+                        );
+                    }
+                },
+                Definition::TypeExtension(type_extension) => match type_extension {
+                    TypeExtension::Scalar(scalar_type) => {
+                        let idx = graph.node(&scalar_type.name);
+                        graph.add_directives(idx, &scalar_type.directives);
+                    }
+                    TypeExtension::Object(object_type) => {
+                        let idx = graph.node(&object_type.name);
+                        graph.add_directives(idx, &object_type.directives);
+                        graph.add_fields(idx, &object_type.fields);
+                        graph.add_interfaces(idx, &object_type.implements_interfaces);
+                    }
+                    TypeExtension::Interface(interface_type) => {
+                        let idx = graph.node(&interface_type.name);
+                        graph.add_directives(idx, &interface_type.directives);
+                        graph.add_fields(idx, &interface_type.fields);
+                        graph.add_interfaces(idx, &interface_type.implements_interfaces);
+                    }
+                    TypeExtension::Union(union_type) => {
+                        let idx = graph.node(&union_type.name);
+                        graph.add_directives(idx, &union_type.directives);
+                        graph.add_members(idx, &union_type.types);
+                    }
+                    TypeExtension::Enum(enum_type) => {
+                        let idx = graph.node(&enum_type.name);
+                        graph.add_directives(idx, &enum_type.directives);
+                        for value in &enum_type.values {
+                            graph.add_directives(idx, &value.directives);
+                        }
+                    }
+                    TypeExtension::InputObject(input_object_type) => {
+                        let idx = graph.node(&input_object_type.name);
+                        graph.add_directives(idx, &input_object_type.directives);
+                        graph.add_input_values(
+                            idx,
+                            EdgeKind::InputField,
+                            &input_object_type.fields,
+                        );
+                    }
+                },
+                Definition::DirectiveDefinition(directive_definition) => {
+                    let idx = graph.directives[&directive_definition.name];
+                    graph.add_input_values(
+                        idx,
+                        EdgeKind::Argument,
+                        &directive_definition.arguments,
+                    );
+                }
+            }
+        }
+
+        graph
+    }
+
+    fn node(&mut self, name: &'s String) -> NodeIndex {
+        *self
+            .nodes
+            .entry(name)
+            .or_insert_with(|| self.graph.add_node(name))
+    }
+
+    fn add_fields(&mut self, idx: NodeIndex, fields: &'s [Field<'_, String>]) {
+        for field in fields {
+            let tn = self.node(util::named_type(&field.field_type).unwrap());
+            self.graph
+                .add_edge(idx, tn, TypeEdge::through(EdgeKind::Field, &field.name));
+            self.add_input_values(idx, EdgeKind::Argument, &field.arguments);
+            self.add_directives(idx, &field.directives);
+        }
+    }
+
+    fn add_input_values(
+        &mut self,
+        idx: NodeIndex,
+        kind: EdgeKind,
+        values: &'s [InputValue<'_, String>],
+    ) {
+        for value in values {
+            let tn = self.node(util::named_type(&value.value_type).unwrap());
+            self.graph
+                .add_edge(idx, tn, TypeEdge::through(kind, &value.name));
+            self.add_directives(idx, &value.directives);
+        }
+    }
+
+    fn add_interfaces(&mut self, idx: NodeIndex, interfaces: &'s [String]) {
+        for i in interfaces {
+            let i_idx = self.node(i);
+            self.graph
+                .add_edge(i_idx, idx, TypeEdge::kind(EdgeKind::Implements));
+        }
+    }
+
+    fn add_members(&mut self, idx: NodeIndex, members: &'s [String]) {
+        for ty in members {
+            let ty_idx = self.node(ty);
+            self.graph
+                .add_edge(idx, ty_idx, TypeEdge::kind(EdgeKind::UnionMember));
+        }
+    }
+
+    fn add_directives(&mut self, idx: NodeIndex, directives: &[Directive<'_, String>]) {
+        for directive in directives {
+            if let Some(d_idx) = self.directives.get(&directive.name) {
+                self.graph
+                    .add_edge(idx, *d_idx, TypeEdge::kind(EdgeKind::Directive));
+            }
+        }
+    }
+}
+
+/// Returns the subset of the schema made up of the focused types and all of
+/// their descendants. The result is empty if none of the types exist.
+pub fn focus<'a>(
+    schema_ast: &Document<'a, String>,
+    options: &FocusOptions,
+) -> Document<'a, String> {
+    let TypeGraph {
+        graph: g,
+        nodes: type_node_map,
+        directives: directive_node_map,
+    } = TypeGraph::new(schema_ast);
+
+    if let Some(depth) = options.depth {
+        return focus_depth(
+            schema_ast,
+            &g,
+            &type_node_map,
+            &directive_node_map,
+            options,
+            depth,
+        );
+    }
+
+    let used: HashSet<&String> = options
+        .types
+        .iter()
+        .flat_map(|t| {
+            if let Some(root_idx) = type_node_map.get(t) {
+                let dfs = petgraph::visit::Dfs::new(&g, *root_idx);
+                return dfs.iter(&g).map(|n| g[n]).collect();
+            }
+            Vec::new()
+        })
+        .collect();
+
+    strip_unused_types(schema_ast, used)
+}
+
+/// Retains the types within `depth` references of the focused types,
+/// searching breadth first, and trims or stubs the types at that depth so the
+/// result stays a valid schema.
+fn focus_depth<'a>(
+    schema_ast: &Document<'a, String>,
+    g: &petgraph::Graph<&String, TypeEdge>,
+    type_node_map: &HashMap<&String, NodeIndex>,
+    directive_node_map: &HashMap<&String, NodeIndex>,
+    options: &FocusOptions,
+    depth: usize,
+) -> Document<'a, String> {
+    let mut distances: HashMap<NodeIndex, usize> = HashMap::new();
+    let mut queue = VecDeque::new();
+    for t in &options.types {
+        if let Some(idx) = type_node_map.get(t) {
+            if distances.insert(*idx, 0).is_none() {
+                queue.push_back(*idx);
+            }
+        }
+    }
+    while let Some(idx) = queue.pop_front() {
+        let distance = distances[&idx];
+        if distance == depth {
+            continue;
+        }
+        for next in g.neighbors(idx) {
+            if let Entry::Vacant(entry) = distances.entry(next) {
+                entry.insert(distance + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    let type_defs: HashMap<&String, &TypeDefinition<String>> = schema_ast
+        .definitions
+        .iter()
+        .filter_map(|def| match def {
+            Definition::TypeDefinition(td) => Some((util::schema_type_definition_name(td)?, td)),
+            _ => None,
+        })
+        .collect();
+
+    let mut retained: HashSet<&String> = distances.keys().map(|idx| g[*idx]).collect();
+    let boundary: Vec<NodeIndex> = distances
+        .iter()
+        .filter(|(_, distance)| **distance == depth)
+        .map(|(idx, _)| *idx)
+        .collect();
+    // Scalars and enums lead nowhere else, so those the boundary refers to
+    // are kept rather than trimmed or stubbed.
+    for idx in &boundary {
+        for next in g.neighbors(*idx) {
+            if matches!(
+                type_defs.get(g[next]),
+                Some(TypeDefinition::Scalar(_) | TypeDefinition::Enum(_))
+            ) {
+                retained.insert(g[next]);
+            }
+        }
+    }
+    let boundary: HashSet<&String> = boundary.iter().map(|idx| g[*idx]).collect();
+
+    let keep = |name: &String| retained.contains(name) || !type_defs.contains_key(name);
+    let mut stubs: HashSet<String> = HashSet::new();
+    let mut stubbed: HashSet<&String> = HashSet::new();
+    // The boundary definitions, trimmed or stubbed, by position.
+    let mut bounded: HashMap<usize, Option<Definition<String>>> = HashMap::new();
+    {
+        let mut keep_field = |field_type: &String| {
+            if keep(field_type) {
+                return true;
+            }
+            if options.boundary == Boundary::Stub {
+                stubs.insert(field_type.clone());
+                return true;
+            }
+            false
+        };
+
+        for (i, def) in schema_ast.definitions.iter().enumerate() {
+            let Definition::TypeDefinition(td) = def else {
+                continue;
+            };
+            let name = util::schema_type_definition_name(td).unwrap();
+            if boundary.contains(name) {
+                let def = match bound_type(td, &keep, &mut keep_field) {
+                    Some(td) => Definition::TypeDefinition(td),
+                    None => {
+                        stubbed.insert(name);
+                        stub(td)
+                    }
+                };
+                bounded.insert(i, Some(def));
+            }
+        }
+        // Extensions of boundary types are trimmed the same way, and dropped
+        // along with the types replaced by stubs.
+        for (i, def) in schema_ast.definitions.iter().enumerate() {
+            let Definition::TypeExtension(ext) = def else {
+                continue;
+            };
+            let name = util::schema_type_extension_name(ext);
+            if boundary.contains(name) {
+                let ext = if stubbed.contains(name) {
+                    None
+                } else {
+                    bound_extension(ext, &keep, &mut keep_field)
+                };
+                bounded.insert(i, ext.map(Definition::TypeExtension));
+            }
+        }
+    }
+
+    // What is left of the boundary definitions still needs the input types of
+    // its arguments and the directives it uses, along with everything those
+    // refer to in turn.
+    let mut pending: Vec<NodeIndex> = Vec::new();
+    for def in bounded.values().flatten() {
+        let (mut types, mut directives) = (Vec::new(), Vec::new());
+        bound_references(def, &mut types, &mut directives);
+        pending.extend(types.into_iter().filter_map(|t| type_node_map.get(t)));
+        pending.extend(
+            directives
+                .into_iter()
+                .filter_map(|d| directive_node_map.get(d)),
+        );
+    }
+    while let Some(idx) = pending.pop() {
+        if retained.insert(g[idx]) {
+            pending.extend(g.neighbors(idx));
+        }
+    }
+
+    let keep = |name: &String| retained.contains(name) || !type_defs.contains_key(name);
+    let definitions = schema_ast
+        .definitions
+        .iter()
+        .enumerate()
+        .filter_map(|(i, def)| {
+            if let Some(bounded) = bounded.get(&i) {
+                return bounded.clone();
+            }
+            match def {
+                Definition::TypeDefinition(td) => {
+                    let name = util::schema_type_definition_name(td)?;
+                    if retained.contains(name) {
+                        retain_interfaces(def, keep)
+                    } else if stubs.contains(name) {
+                        Some(stub(td))
+                    } else {
+                        None
+                    }
+                }
+                Definition::TypeExtension(ext) => {
+                    if retained.contains(util::schema_type_extension_name(ext)) {
+                        retain_interfaces(def, keep)
+                    } else {
+                        None
+                    }
+                }
+                Definition::DirectiveDefinition(directive) => {
+                    retained.contains(&directive.name).then(|| def.clone())
+                }
+                Definition::SchemaDefinition(_) => None,
+            }
+        })
+        .collect();
+
+    Document { definitions }
+}
+
+/// Removes the fields, interfaces and union members of a boundary type that
+/// refer to types outside the focus. Returns `None` when nothing is left.
+fn bound_type<'a>(
+    td: &TypeDefinition<'a, String>,
+    keep: &impl Fn(&String) -> bool,
+    keep_field: &mut impl FnMut(&String) -> bool,
+) -> Option<TypeDefinition<'a, String>> {
+    let bounded = match td {
+        TypeDefinition::Object(obj) => TypeDefinition::Object(ObjectType {
+            fields: bound_fields(&obj.fields, keep_field),
+            implements_interfaces: bound_names(&obj.implements_interfaces, keep),
+            ..obj.clone()
+        }),
+        TypeDefinition::Interface(iface) => TypeDefinition::Interface(InterfaceType {
+            fields: bound_fields(&iface.fields, keep_field),
+            implements_interfaces: bound_names(&iface.implements_interfaces, keep),
+            ..iface.clone()
+        }),
+        TypeDefinition::Union(union) => TypeDefinition::Union(UnionType {
+            types: bound_names(&union.types, keep),
+            ..union.clone()
+        }),
+        TypeDefinition::InputObject(input) => TypeDefinition::InputObject(InputObjectType {
+            fields: bound_input_values(&input.fields, keep_field),
+            ..input.clone()
+        }),
+        TypeDefinition::Scalar(_) | TypeDefinition::Enum(_) => td.clone(),
+    };
+
+    let empty = match &bounded {
+        TypeDefinition::Object(obj) => obj.fields.is_empty(),
+        TypeDefinition::Interface(iface) => iface.fields.is_empty(),
+        TypeDefinition::Union(union) => union.types.is_empty(),
+        TypeDefinition::InputObject(input) => input.fields.is_empty(),
+        TypeDefinition::Scalar(_) | TypeDefinition::Enum(_) => false,
+    };
+    (!empty).then_some(bounded)
+}
+
+/// Trims an extension of a boundary type like [`bound_type`]. Returns `None`
+/// when the extension no longer adds anything.
+fn bound_extension<'a>(
+    ext: &TypeExtension<'a, String>,
+    keep: &impl Fn(&String) -> bool,
+    keep_field: &mut impl FnMut(&String) -> bool,
+) -> Option<TypeExtension<'a, String>> {
+    let bounded = match ext {
+        TypeExtension::Object(obj) => TypeExtension::Object(ObjectTypeExtension {
+            fields: bound_fields(&obj.fields, keep_field),
+            implements_interfaces: bound_names(&obj.implements_interfaces, keep),
+            ..obj.clone()
+        }),
+        TypeExtension::Interface(iface) => TypeExtension::Interface(InterfaceTypeExtension {
+            fields: bound_fields(&iface.fields, keep_field),
+            implements_interfaces: bound_names(&iface.implements_interfaces, keep),
+            ..iface.clone()
+        }),
+        TypeExtension::Union(union) => TypeExtension::Union(UnionTypeExtension {
+            types: bound_names(&union.types, keep),
+            ..union.clone()
+        }),
+        TypeExtension::InputObject(input) => TypeExtension::InputObject(InputObjectTypeExtension {
+            fields: bound_input_values(&input.fields, keep_field),
+            ..input.clone()
+        }),
+        TypeExtension::Scalar(_) | TypeExtension::Enum(_) => ext.clone(),
+    };
+
+    let empty = match &bounded {
+        TypeExtension::Object(obj) => {
+            obj.fields.is_empty()
+                && obj.implements_interfaces.is_empty()
+                && obj.directives.is_empty()
+        }
+        TypeExtension::Interface(iface) => {
+            iface.fields.is_empty()
+                && iface.implements_interfaces.is_empty()
+                && iface.directives.is_empty()
+        }
+        TypeExtension::Union(union) => union.types.is_empty() && union.directives.is_empty(),
+        TypeExtension::InputObject(input) => input.fields.is_empty() && input.directives.is_empty(),
+        TypeExtension::Scalar(_) | TypeExtension::Enum(_) => false,
+    };
+    (!empty).then_some(bounded)
+}
+
+fn bound_fields<'a>(
+    fields: &[Field<'a, String>],
+    keep_field: &mut impl FnMut(&String) -> bool,
+) -> Vec<Field<'a, String>> {
+    fields
+        .iter()
+        .filter(|f| keep_field(util::named_type(&f.field_type).unwrap()))
+        .cloned()
+        .collect()
+}
+
+fn bound_input_values<'a>(
+    values: &[InputValue<'a, String>],
+    keep_field: &mut impl FnMut(&String) -> bool,
+) -> Vec<InputValue<'a, String>> {
+    values
+        .iter()
+        .filter(|v| keep_field(util::named_type(&v.value_type).unwrap()))
+        .cloned()
+        .collect()
+}
+
+/// Collects the argument types and the directives used by a trimmed boundary
+/// definition. The types of its fields and input fields are left out, since
+/// trimming and stubbing already take care of them.
+fn bound_references<'d>(
+    def: &'d Definition<'_, String>,
+    types: &mut Vec<&'d String>,
+    directives: &mut Vec<&'d String>,
+) {
+    let mut fields = |fields: &'d [Field<'_, String>], directives: &mut Vec<&'d String>| {
+        for field in fields {
+            directives.extend(field.directives.iter().map(|d| &d.name));
+            for argument in &field.arguments {
+                types.push(util::named_type(&argument.value_type).unwrap());
+                directives.extend(argument.directives.iter().map(|d| &d.name));
+            }
+        }
+    };
+    let input_fields = |fields: &'d [InputValue<'_, String>], directives: &mut Vec<&'d String>| {
+        for field in fields {
+            directives.extend(field.directives.iter().map(|d| &d.name));
+        }
+    };
+    let enum_values = |values: &'d [EnumValue<'_, String>], directives: &mut Vec<&'d String>| {
+        for value in values {
+            directives.extend(value.directives.iter().map(|d| &d.name));
+        }
+    };
+
+    let own = match def {
+        Definition::TypeDefinition(td) => match td {
+            TypeDefinition::Scalar(t) => &t.directives,
+            TypeDefinition::Object(t) => {
+                fields(&t.fields, directives);
+                &t.directives
+            }
+            TypeDefinition::Interface(t) => {
+                fields(&t.fields, directives);
+                &t.directives
+            }
+            TypeDefinition::Union(t) => &t.directives,
+            TypeDefinition::Enum(t) => {
+                enum_values(&t.values, directives);
+                &t.directives
+            }
+            TypeDefinition::InputObject(t) => {
+                input_fields(&t.fields, directives);
+                &t.directives
+            }
+        },
+        Definition::TypeExtension(ext) => match ext {
+            TypeExtension::Scalar(t) => &t.directives,
+            TypeExtension::Object(t) => {
+                fields(&t.fields, directives);
+                &t.directives
+            }
+            TypeExtension::Interface(t) => {
+                fields(&t.fields, directives);
+                &t.directives
+            }
+            TypeExtension::Union(t) => &t.directives,
+            TypeExtension::Enum(t) => {
+                enum_values(&t.values, directives);
+                &t.directives
+            }
+            TypeExtension::InputObject(t) => {
+                input_fields(&t.fields, directives);
+                &t.directives
+            }
+        },
+        Definition::SchemaDefinition(_) | Definition::DirectiveDefinition(_) => return,
+    };
+    directives.extend(own.iter().map(|d| &d.name));
+}
+
+fn bound_names(names: &[String], keep: &impl Fn(&String) -> bool) -> Vec<String> {
+    names.iter().filter(|name| keep(name)).cloned().collect()
+}
+
+/// A scalar standing in for a type outside the focus, keeping its
+/// description.
+fn stub<'a>(td: &TypeDefinition<'a, String>) -> Definition<'a, String> {
+    let name = util::schema_type_definition_name(td).unwrap().clone();
+    let description = match td {
+        TypeDefinition::Scalar(t) => &t.description,
+        TypeDefinition::Object(t) => &t.description,
+        TypeDefinition::Interface(t) => &t.description,
+        TypeDefinition::Union(t) => &t.description,
+        TypeDefinition::Enum(t) => &t.description,
+        TypeDefinition::InputObject(t) => &t.description,
+    };
+    Definition::TypeDefinition(TypeDefinition::Scalar(ScalarType {
+        description: description.clone(),
+        ..ScalarType::new(name)
+    }))
+}
+
+/// Removes unused types from the GraphQL schema.
+/// It filters out definitions that are not in the set of used types and returns the modified schema.
+pub(crate) fn strip_unused_types<'a>(
+    schema: &Document<'a, String>,
+    used_types: HashSet<&String>,
+) -> Document<'a, String> {
+    let definitions = schema
+        .definitions
+        .iter()
+        .filter(|def| match def {
+            Definition::TypeExtension(ext) => {
+                used_types.contains(util::schema_type_extension_name(ext))
+            }
+            _ => util::schema_definition_name(def)
+                .map_or_else(|| false, |name| used_types.contains(name)),
+        })
+        .filter_map(|def| retain_interfaces(def, |name| used_types.contains(name)))
+        .collect();
+
+    Document { definitions }
+}
+
+/// Removes the interfaces left out of the focus from the types implementing
+/// them, since focusing on a type does not reach the interfaces it
+/// implements. Returns `None` for extensions left with nothing to add.
+fn retain_interfaces<'a>(
+    def: &Definition<'a, String>,
+    keep: impl Fn(&String) -> bool,
+) -> Option<Definition<'a, String>> {
+    let def = match def {
+        Definition::TypeDefinition(TypeDefinition::Object(obj)) => {
+            Definition::TypeDefinition(TypeDefinition::Object(ObjectType {
+                implements_interfaces: bound_names(&obj.implements_interfaces, &keep),
+                ..obj.clone()
+            }))
+        }
+        Definition::TypeDefinition(TypeDefinition::Interface(iface)) => {
+            Definition::TypeDefinition(TypeDefinition::Interface(InterfaceType {
+                implements_interfaces: bound_names(&iface.implements_interfaces, &keep),
+                ..iface.clone()
+            }))
+        }
+        Definition::TypeExtension(TypeExtension::Object(obj)) => {
+            let implements_interfaces = bound_names(&obj.implements_interfaces, &keep);
+            if obj.fields.is_empty()
+                && implements_interfaces.is_empty()
+                && obj.directives.is_empty()
+            {
+                return None;
+            }
+            Definition::TypeExtension(TypeExtension::Object(ObjectTypeExtension {
+                implements_interfaces,
+                ..obj.clone()
+            }))
+        }
+        Definition::TypeExtension(TypeExtension::Interface(iface)) => {
+            let implements_interfaces = bound_names(&iface.implements_interfaces, &keep);
+            if iface.fields.is_empty()
+                && implements_interfaces.is_empty()
+                && iface.directives.is_empty()
+            {
+                return None;
+            }
+            Definition::TypeExtension(TypeExtension::Interface(InterfaceTypeExtension {
+                implements_interfaces,
+                ..iface.clone()
+            }))
+        }
+        _ => def.clone(),
+    };
+    Some(def)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{focus, Boundary, FocusOptions};
+    use graphql_parser::parse_schema;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    fn process(schema: &str, types: &[&str]) -> String {
+        let schema = parse_schema::<String>(schema).unwrap();
+        let options = FocusOptions {
+            types: types.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        };
+        focus(&schema, &options).to_string()
+    }
+
+    #[test]
+    fn test_focus_query_operation() {
+        let schema = indoc! {"
+            type Query {
+              user: User
+            }
+
+            type User {
+              id: ID
+              name: String
+            }
+        "};
+
+        let result = process(schema, &["User"]);
+        let expected_schema = indoc! {"
+            type User {
+              id: ID
+              name: String
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_focus_multiple_types() {
+        let schema = indoc! {"
+            type Query {
+              user: User
+              company: Company
+            }
+
+            type User {
+              id: ID
+              name: String
+            }
+
+            type Company {
+              id: ID
+              name: String
+            }
+        "};
+
+        let result = process(schema, &["User", "Company"]);
+        let expected_schema = indoc! {"
+            type User {
+              id: ID
+              name: String
+            }
+
+            type Company {
+              id: ID
+              name: String
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_focus_interface() {
+        let schema = indoc! {"
+            type Query {
+              user: User
+            }
+
+            interface Person {
+              name: String
+            }
+
+            type User implements Person {
+              id: ID
+              name: String
+              admin: Bool
+            }
+
+            type Guest implements Person {
+              id: ID
+              name: String
+            }
+        "};
+
+        let result = process(schema, &["Person"]);
+        let expected_schema = indoc! {"
+            interface Person {
+              name: String
+            }
+
+            type User implements Person {
+              id: ID
+              name: String
+              admin: Bool
+            }
+
+            type Guest implements Person {
+              id: ID
+              name: String
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_nested_interface() {
+        let schema = indoc! {"
+            type Query {
+                company: Company
+            }
+
+            type Company {
+              employees: [Person]
+            }
+
+            interface Person {
+              name: String
+            }
+
+            type User implements Person {
+              id: ID
+              name: String
+              admin: Bool
+            }
+
+            type Guest implements Person {
+              id: ID
+              name: String
+            }
+        "};
+
+        let result = process(schema, &["Company"]);
+        let expected_schema = indoc! {"
+            type Company {
+              employees: [Person]
+            }
+
+            interface Person {
+              name: String
+            }
+
+            type User implements Person {
+              id: ID
+              name: String
+              admin: Bool
+            }
+
+            type Guest implements Person {
+              id: ID
+              name: String
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_non_null_nested_interface() {
+        let schema = indoc! {"
+            type Query {
+                company: Company
+            }
+
+            type Company {
+              employees: Person!
+            }
+
+            interface Person {
+              name: String
+            }
+
+            type User implements Person {
+              id: ID
+              name: String
+              admin: Bool
+            }
+
+            type Guest implements Person {
+              id: ID
+              name: String
+            }
+        "};
+
+        let result = process(schema, &["Company"]);
+        let expected_schema = indoc! {"
+            type Company {
+              employees: Person!
+            }
+
+            interface Person {
+              name: String
+            }
+
+            type User implements Person {
+              id: ID
+              name: String
+              admin: Bool
+            }
+
+            type Guest implements Person {
+              id: ID
+              name: String
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_focus_query_missing_operation() {
+        let schema = indoc! {"
+            type Query {
+              user: User
+            }
+
+            type User {
+              id: ID
+              name: String
+            }
+        "};
+
+        let result = process(schema, &["nonExistent"]);
+        assert_eq!(result.trim(), "");
+    }
+
+    #[test]
+    fn test_focus_nested_types() {
+        let schema = indoc! {"
+            type Query {
+              user: User
+            }
+
+            type User {
+              id: ID
+              profile: Profile
+            }
+
+            type Profile {
+              email: String
+            }
+        "};
+
+        let result = process(schema, &["User"]);
+        let expected_schema = indoc! {"
+            type User {
+              id: ID
+              profile: Profile
+            }
+
+            type Profile {
+              email: String
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_focus_unused_types() {
+        let schema = indoc! {"
+            type Query {
+              user: User
+            }
+
+            type User {
+              id: ID
+              profile: Profile
+            }
+
+            type Profile {
+              email: String
+            }
+
+            type UnusedType {
+              field: String
+            }
+        "};
+
+        let result = process(schema, &["User"]);
+        let expected_schema = indoc! {"
+            type User {
+              id: ID
+              profile: Profile
+            }
+
+            type Profile {
+              email: String
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_focus_arguments_extensions_and_directives() {
+        let schema = indoc! {"
+            directive @auth(requires: Role) on FIELD_DEFINITION
+
+            directive @unrelated on OBJECT
+
+            type Query {
+              user: User
+            }
+
+            type User {
+              id: ID
+              posts(filter: PostFilter): [Post] @auth(requires: ADMIN)
+            }
+
+            input PostFilter {
+              published: Boolean
+            }
+
+            enum Role {
+              ADMIN
+            }
+
+            type Post {
+              title: String
+            }
+
+            extend type Post {
+              author: Author
+            }
+
+            type Author {
+              name: String
+            }
+
+            type Unrelated @unrelated {
+              id: ID
+            }
+        "};
+
+        let result = process(schema, &["User"]);
+        let expected_schema = indoc! {"
+            directive @auth(requires: Role) on FIELD_DEFINITION
+
+            type User {
+              id: ID
+              posts(filter: PostFilter): [Post] @auth(requires: ADMIN)
+            }
+
+            input PostFilter {
+              published: Boolean
+            }
+
+            enum Role {
+              ADMIN
+            }
+
+            type Post {
+              title: String
+            }
+
+            extend type Post {
+              author: Author
+            }
+
+            type Author {
+              name: String
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    const DEPTH_SCHEMA: &str = indoc! {"
+        type User {
+          id: ID
+          role: Role
+          profile: Profile
+        }
+
+        enum Role {
+          ADMIN
+        }
+
+        type Profile {
+          bio: String
+          avatar: Image
+          owner: User
+        }
+
+        \"An uploaded image\"
+        type Image {
+          url: String
+          sizes: [Size]
+        }
+
+        type Size {
+          width: Int
+        }
+    "};
+
+    fn process_depth(depth: usize, boundary: Boundary) -> String {
+        let schema = parse_schema::<String>(DEPTH_SCHEMA).unwrap();
+        let options = FocusOptions {
+            types: vec!["User".to_string()],
+            depth: Some(depth),
+            boundary,
+        };
+        focus(&schema, &options).to_string()
+    }
+
+    #[test]
+    fn test_focus_depth_trims_boundary() {
+        let result = process_depth(1, Boundary::Trim);
+        let expected_schema = indoc! {"
+            type User {
+              id: ID
+              role: Role
+              profile: Profile
+            }
+
+            enum Role {
+              ADMIN
+            }
+
+            type Profile {
+              bio: String
+              owner: User
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_focus_depth_stubs_boundary() {
+        let result = process_depth(1, Boundary::Stub);
+        let expected_schema = indoc! {"
+            type User {
+              id: ID
+              role: Role
+              profile: Profile
+            }
+
+            enum Role {
+              ADMIN
+            }
+
+            type Profile {
+              bio: String
+              avatar: Image
+              owner: User
+            }
+
+            \"An uploaded image\"
+            scalar Image
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_focus_depth_zero() {
+        let result = process_depth(0, Boundary::Trim);
+        let expected_schema = indoc! {"
+            type User {
+              id: ID
+              role: Role
+            }
+
+            enum Role {
+              ADMIN
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_focus_depth_keeps_boundary_arguments_and_directives() {
+        let schema = parse_schema::<String>(indoc! {"
+            directive @cost(weight: Weight) on FIELD_DEFINITION
+
+            scalar Weight
+
+            type User {
+              posts: [Post]
+            }
+
+            type Post @key(fields: \"id\") {
+              count(where: CommentFilter): Int @cost(weight: 1)
+              comments(where: CommentFilter): [Comment]
+            }
+
+            directive @key(fields: String) on OBJECT
+
+            input CommentFilter {
+              author: AuthorFilter
+            }
+
+            input AuthorFilter {
+              name: String
+            }
+
+            type Comment {
+              body: String
+            }
+        "})
+        .unwrap();
+        let process = |boundary| {
+            let options = FocusOptions {
+                types: vec!["User".to_string()],
+                depth: Some(1),
+                boundary,
+            };
+            focus(&schema, &options).to_string()
+        };
+
+        assert_eq!(
+            process(Boundary::Trim),
+            indoc! {r#"
+                directive @cost(weight: Weight) on FIELD_DEFINITION
+
+                scalar Weight
+
+                type User {
+                  posts: [Post]
+                }
+
+                type Post @key(fields: "id") {
+                  count(where: CommentFilter): Int @cost(weight: 1)
+                }
+
+                directive @key(fields: String) on OBJECT
+
+                input CommentFilter {
+                  author: AuthorFilter
+                }
+
+                input AuthorFilter {
+                  name: String
+                }
+            "#}
+        );
+        assert_eq!(
+            process(Boundary::Stub),
+            indoc! {r#"
+                directive @cost(weight: Weight) on FIELD_DEFINITION
+
+                scalar Weight
+
+                type User {
+                  posts: [Post]
+                }
+
+                type Post @key(fields: "id") {
+                  count(where: CommentFilter): Int @cost(weight: 1)
+                  comments(where: CommentFilter): [Comment]
+                }
+
+                directive @key(fields: String) on OBJECT
+
+                input CommentFilter {
+                  author: AuthorFilter
+                }
+
+                input AuthorFilter {
+                  name: String
+                }
+
+                scalar Comment
+            "#}
+        );
+    }
+
+    #[test]
+    fn test_focus_drops_unfocused_interfaces() {
+        let schema = parse_schema::<String>(indoc! {"
+            type Query {
+              viewer: User
+            }
+
+            interface Node {
+              id: ID!
+            }
+
+            type User implements Node {
+              id: ID!
+              profile: Profile
+            }
+
+            extend type User implements Entity
+
+            interface Entity {
+              id: ID!
+            }
+
+            type Profile implements Node {
+              id: ID!
+            }
+        "})
+        .unwrap();
+        let process = |types: &[&str], depth| {
+            let options = FocusOptions {
+                types: types.iter().map(|t| t.to_string()).collect(),
+                depth,
+                boundary: Boundary::Trim,
+            };
+            focus(&schema, &options).to_string()
+        };
+
+        assert_eq!(
+            process(&["User"], None),
+            indoc! {"
+                type User {
+                  id: ID!
+                  profile: Profile
+                }
+
+                type Profile {
+                  id: ID!
+                }
+            "}
+        );
+        assert_eq!(
+            process(&["Query"], Some(2)),
+            indoc! {"
+                type Query {
+                  viewer: User
+                }
+
+                type User {
+                  id: ID!
+                  profile: Profile
+                }
+
+                type Profile {
+                  id: ID!
+                }
+            "}
+        );
+        assert_eq!(
+            process(&["Node"], None),
+            indoc! {"
+                interface Node {
+                  id: ID!
+                }
+
+                type User implements Node {
+                  id: ID!
+                  profile: Profile
+                }
+
+                type Profile implements Node {
+                  id: ID!
+                }
+            "}
+        );
+    }
+}
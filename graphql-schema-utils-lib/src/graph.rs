@@ -0,0 +1,298 @@
+use crate::focus::{focus, EdgeKind, FocusOptions, TypeGraph};
+use crate::util;
+use graphql_parser::schema::{Definition, Document, TypeDefinition};
+use petgraph::visit::EdgeRef;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// Output formats for [`graph`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// A Graphviz `digraph`.
+    #[default]
+    Dot,
+    /// A Mermaid flowchart.
+    Mermaid,
+    /// The nodes and edges as JSON.
+    Json,
+}
+
+/// Options for [`graph`].
+#[derive(Debug, Clone, Default)]
+pub struct GraphOptions {
+    /// Only include these types and the types reachable from them, as
+    /// [`focus`](crate::focus) would. Every type is included when empty.
+    pub types: Vec<String>,
+    pub format: GraphFormat,
+}
+
+#[derive(Serialize)]
+struct Graph<'s> {
+    nodes: Vec<Node<'s>>,
+    edges: Vec<Edge<'s>>,
+}
+
+#[derive(Serialize)]
+struct Node<'s> {
+    name: &'s str,
+    kind: &'static str,
+}
+
+#[derive(Serialize)]
+struct Edge<'s> {
+    from: &'s str,
+    to: &'s str,
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    field: Option<&'s str>,
+}
+
+/// Renders the type graph [`focus`](crate::focus) traverses. Edges are
+/// labeled with the field or argument they go through, or with their kind
+/// for interface implementations and union members, and only connect types
+/// the schema defines.
+pub fn graph(schema_ast: &Document<'_, String>, options: &GraphOptions) -> String {
+    let focused;
+    let schema_ast = if options.types.is_empty() {
+        schema_ast
+    } else {
+        focused = focus(
+            schema_ast,
+            &FocusOptions {
+                types: options.types.clone(),
+                ..Default::default()
+            },
+        );
+        &focused
+    };
+
+    let graph = collect(schema_ast);
+    match options.format {
+        GraphFormat::Dot => dot(&graph),
+        GraphFormat::Mermaid => mermaid(&graph),
+        GraphFormat::Json => serde_json::to_string_pretty(&graph).unwrap() + "\n",
+    }
+}
+
+fn collect<'s>(schema_ast: &'s Document<'_, String>) -> Graph<'s> {
+    let nodes: Vec<Node> = schema_ast
+        .definitions
+        .iter()
+        .filter_map(|def| match def {
+            Definition::TypeDefinition(td) => Some(Node {
+                name: util::schema_type_definition_name(td).unwrap(),
+                kind: type_kind(td),
+            }),
+            _ => None,
+        })
+        .collect();
+    let defined: HashSet<&str> = nodes.iter().map(|node| node.name).collect();
+
+    let TypeGraph { graph, .. } = TypeGraph::new(schema_ast);
+    let edges = graph
+        .edge_references()
+        .filter_map(|edge| {
+            let from = graph[edge.source()].as_str();
+            let to = graph[edge.target()].as_str();
+            if !defined.contains(from) || !defined.contains(to) {
+                return None;
+            }
+
+            let weight = edge.weight();
+            // Implementing types point at their interfaces, as they do in SDL.
+            let (from, to) = match weight.kind {
+                EdgeKind::Implements => (to, from),
+                _ => (from, to),
+            };
+            Some(Edge {
+                from,
+                to,
+                kind: edge_kind(weight.kind),
+                field: weight.field.map(String::as_str),
+            })
+        })
+        .collect();
+
+    Graph { nodes, edges }
+}
+
+fn type_kind(td: &TypeDefinition<'_, String>) -> &'static str {
+    match td {
+        TypeDefinition::Scalar(_) => "scalar",
+        TypeDefinition::Object(_) => "object",
+        TypeDefinition::Interface(_) => "interface",
+        TypeDefinition::Union(_) => "union",
+        TypeDefinition::Enum(_) => "enum",
+        TypeDefinition::InputObject(_) => "input_object",
+    }
+}
+
+fn edge_kind(kind: EdgeKind) -> &'static str {
+    match kind {
+        EdgeKind::Field => "field",
+        EdgeKind::Argument => "argument",
+        EdgeKind::Implements => "implements",
+        EdgeKind::UnionMember => "union_member",
+        EdgeKind::InputField => "input_field",
+        EdgeKind::Directive => "directive",
+    }
+}
+
+/// The text an edge is labeled with in diagrams.
+fn label<'s>(edge: &Edge<'s>) -> &'s str {
+    match edge.field {
+        Some(field) => field,
+        None if edge.kind == "implements" => "implements",
+        None => "member",
+    }
+}
+
+fn dot(graph: &Graph) -> String {
+    let mut out = String::from("digraph schema {\n");
+    for node in &graph.nodes {
+        writeln!(out, "  \"{}\";", node.name).unwrap();
+    }
+    for edge in &graph.edges {
+        let style = match edge.kind {
+            "implements" => ", style=dashed",
+            "union_member" => ", style=dotted",
+            "input_field" => ", style=bold",
+            "argument" => ", arrowhead=empty",
+            _ => "",
+        };
+        writeln!(
+            out,
+            "  \"{}\" -> \"{}\" [label=\"{}\"{style}];",
+            edge.from,
+            edge.to,
+            label(edge)
+        )
+        .unwrap();
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn mermaid(graph: &Graph) -> String {
+    let mut out = String::from("graph LR\n");
+    for node in &graph.nodes {
+        writeln!(out, "  {}", node.name).unwrap();
+    }
+    for edge in &graph.edges {
+        let arrow = match edge.kind {
+            "implements" | "union_member" => "-.->",
+            "input_field" => "==>",
+            "argument" => "--o",
+            _ => "-->",
+        };
+        writeln!(out, "  {} {arrow}|{}| {}", edge.from, label(edge), edge.to).unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{graph, GraphFormat, GraphOptions};
+    use graphql_parser::parse_schema;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    const SCHEMA: &str = indoc! {"
+        type Query {
+          search(filter: SearchFilter): [SearchResult]
+        }
+
+        interface Node {
+          id: ID!
+        }
+
+        type User implements Node {
+          id: ID!
+          friends: [User]
+        }
+
+        type Post {
+          author: User
+        }
+
+        union SearchResult = User | Post
+
+        input SearchFilter {
+          kind: Kind
+        }
+
+        enum Kind {
+          USER
+          POST
+        }
+    "};
+
+    fn process(types: &[&str], format: GraphFormat) -> String {
+        let schema = parse_schema::<String>(SCHEMA).unwrap();
+        let options = GraphOptions {
+            types: types.iter().map(|t| t.to_string()).collect(),
+            format,
+        };
+        graph(&schema, &options)
+    }
+
+    #[test]
+    fn renders_dot() {
+        assert_eq!(
+            process(&[], GraphFormat::Dot),
+            indoc! {r#"
+                digraph schema {
+                  "Query";
+                  "Node";
+                  "User";
+                  "Post";
+                  "SearchResult";
+                  "SearchFilter";
+                  "Kind";
+                  "Query" -> "SearchResult" [label="search"];
+                  "Query" -> "SearchFilter" [label="filter", arrowhead=empty];
+                  "User" -> "User" [label="friends"];
+                  "User" -> "Node" [label="implements", style=dashed];
+                  "Post" -> "User" [label="author"];
+                  "SearchResult" -> "User" [label="member", style=dotted];
+                  "SearchResult" -> "Post" [label="member", style=dotted];
+                  "SearchFilter" -> "Kind" [label="kind", style=bold];
+                }
+            "#}
+        );
+    }
+
+    #[test]
+    fn renders_focused_mermaid() {
+        assert_eq!(
+            process(&["Post"], GraphFormat::Mermaid),
+            indoc! {"
+                graph LR
+                  User
+                  Post
+                  User -->|friends| User
+                  Post -->|author| User
+            "}
+        );
+    }
+
+    #[test]
+    fn renders_json() {
+        let json: serde_json::Value =
+            serde_json::from_str(&process(&["SearchFilter"], GraphFormat::Json)).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "nodes": [
+                    { "name": "SearchFilter", "kind": "input_object" },
+                    { "name": "Kind", "kind": "enum" },
+                ],
+                "edges": [
+                    { "from": "SearchFilter", "to": "Kind", "kind": "input_field", "field": "kind" },
+                ],
+            })
+        );
+    }
+}
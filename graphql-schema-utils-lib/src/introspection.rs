@@ -0,0 +1,1161 @@
+use graphql_parser::query::{self, OperationDefinition};
+use graphql_parser::schema::{
+    parse_schema, Definition, Directive, DirectiveDefinition, DirectiveLocation, Document,
+    EnumType, EnumValue, Field, InputObjectType, InputValue, InterfaceType, ObjectType, ScalarType,
+    SchemaDefinition, Type, TypeDefinition, UnionType, Value,
+};
+use graphql_parser::Pos;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// Scalars every GraphQL schema provides, which SDL leaves implicit.
+const BUILT_IN_SCALARS: [&str; 5] = ["String", "Int", "Float", "Boolean", "ID"];
+
+/// Directives every GraphQL schema provides, which SDL leaves implicit.
+const BUILT_IN_DIRECTIVES: [&str; 4] = ["skip", "include", "deprecated", "specifiedBy"];
+
+/// The built-in scalars, directives and introspection types, as a server
+/// reports them when introspected.
+pub(crate) const BUILT_IN_SDL: &str = r#"
+scalar String
+
+scalar Int
+
+scalar Float
+
+scalar Boolean
+
+scalar ID
+
+directive @include(if: Boolean!) on FIELD | FRAGMENT_SPREAD | INLINE_FRAGMENT
+
+directive @skip(if: Boolean!) on FIELD | FRAGMENT_SPREAD | INLINE_FRAGMENT
+
+directive @deprecated(reason: String = "No longer supported") on FIELD_DEFINITION | ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION | ENUM_VALUE
+
+directive @specifiedBy(url: String!) on SCALAR
+
+type __Schema {
+  description: String
+  types: [__Type!]!
+  queryType: __Type!
+  mutationType: __Type
+  subscriptionType: __Type
+  directives: [__Directive!]!
+}
+
+type __Type {
+  kind: __TypeKind!
+  name: String
+  description: String
+  specifiedByURL: String
+  fields(includeDeprecated: Boolean = false): [__Field!]
+  interfaces: [__Type!]
+  possibleTypes: [__Type!]
+  enumValues(includeDeprecated: Boolean = false): [__EnumValue!]
+  inputFields(includeDeprecated: Boolean = false): [__InputValue!]
+  ofType: __Type
+}
+
+enum __TypeKind {
+  SCALAR
+  OBJECT
+  INTERFACE
+  UNION
+  ENUM
+  INPUT_OBJECT
+  LIST
+  NON_NULL
+}
+
+type __Field {
+  name: String!
+  description: String
+  args(includeDeprecated: Boolean = false): [__InputValue!]!
+  type: __Type!
+  isDeprecated: Boolean!
+  deprecationReason: String
+}
+
+type __InputValue {
+  name: String!
+  description: String
+  type: __Type!
+  defaultValue: String
+  isDeprecated: Boolean!
+  deprecationReason: String
+}
+
+type __EnumValue {
+  name: String!
+  description: String
+  isDeprecated: Boolean!
+  deprecationReason: String
+}
+
+type __Directive {
+  name: String!
+  description: String
+  isRepeatable: Boolean!
+  locations: [__DirectiveLocation!]!
+  args(includeDeprecated: Boolean = false): [__InputValue!]!
+}
+
+enum __DirectiveLocation {
+  QUERY
+  MUTATION
+  SUBSCRIPTION
+  FIELD
+  FRAGMENT_DEFINITION
+  FRAGMENT_SPREAD
+  INLINE_FRAGMENT
+  VARIABLE_DEFINITION
+  SCHEMA
+  SCALAR
+  OBJECT
+  FIELD_DEFINITION
+  ARGUMENT_DEFINITION
+  INTERFACE
+  UNION
+  ENUM
+  ENUM_VALUE
+  INPUT_OBJECT
+  INPUT_FIELD_DEFINITION
+}
+"#;
+
+/// The reason graphql-js reports for `@deprecated` without an explicit reason.
+const DEFAULT_DEPRECATION_REASON: &str = "No longer supported";
+
+/// Errors returned when an introspection result cannot be converted to SDL.
+#[derive(Debug)]
+pub enum IntrospectionError {
+    Json(serde_json::Error),
+    /// Neither `__schema` nor `data.__schema` was present.
+    MissingSchema,
+    /// A type reference had no name and no wrapped type.
+    InvalidTypeRef,
+    InvalidDefaultValue(String),
+    InvalidDirectiveLocation(String),
+}
+
+impl Display for IntrospectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntrospectionError::Json(err) => write!(f, "invalid introspection JSON: {err}"),
+            IntrospectionError::MissingSchema => {
+                f.write_str("introspection result has no `__schema` field")
+            }
+            IntrospectionError::InvalidTypeRef => {
+                f.write_str("type reference has neither a name nor an `ofType`")
+            }
+            IntrospectionError::InvalidDefaultValue(value) => {
+                write!(f, "invalid default value `{value}`")
+            }
+            IntrospectionError::InvalidDirectiveLocation(location) => {
+                write!(f, "invalid directive location `{location}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for IntrospectionError {}
+
+impl From<serde_json::Error> for IntrospectionError {
+    fn from(err: serde_json::Error) -> Self {
+        IntrospectionError::Json(err)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct IntrospectionResult {
+    #[serde(rename = "__schema")]
+    schema: IntrospectionSchema,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IntrospectionSchema {
+    query_type: Option<NamedRef>,
+    mutation_type: Option<NamedRef>,
+    subscription_type: Option<NamedRef>,
+    types: Vec<FullType>,
+    #[serde(default)]
+    directives: Vec<IntrospectionDirective>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct NamedRef {
+    name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum TypeKind {
+    Scalar,
+    Object,
+    Interface,
+    Union,
+    Enum,
+    InputObject,
+    List,
+    NonNull,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FullType {
+    kind: TypeKind,
+    name: String,
+    description: Option<String>,
+    fields: Option<Vec<IntrospectionField>>,
+    input_fields: Option<Vec<IntrospectionInputValue>>,
+    interfaces: Option<Vec<TypeRef>>,
+    enum_values: Option<Vec<IntrospectionEnumValue>>,
+    possible_types: Option<Vec<TypeRef>>,
+    #[serde(rename = "specifiedByURL")]
+    specified_by_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TypeRef {
+    kind: TypeKind,
+    name: Option<String>,
+    of_type: Option<Box<TypeRef>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IntrospectionField {
+    name: String,
+    description: Option<String>,
+    #[serde(default)]
+    args: Vec<IntrospectionInputValue>,
+    #[serde(rename = "type")]
+    field_type: TypeRef,
+    #[serde(default)]
+    is_deprecated: bool,
+    deprecation_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IntrospectionInputValue {
+    name: String,
+    description: Option<String>,
+    #[serde(rename = "type")]
+    value_type: TypeRef,
+    default_value: Option<String>,
+    #[serde(default)]
+    is_deprecated: bool,
+    deprecation_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IntrospectionEnumValue {
+    name: String,
+    description: Option<String>,
+    #[serde(default)]
+    is_deprecated: bool,
+    deprecation_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IntrospectionDirective {
+    name: String,
+    description: Option<String>,
+    locations: Vec<String>,
+    #[serde(default)]
+    args: Vec<IntrospectionInputValue>,
+    #[serde(default)]
+    is_repeatable: bool,
+}
+
+/// Converts the JSON result of the standard `IntrospectionQuery` into a schema
+/// document. Both the raw response (`{"data": {"__schema": ...}}`) and the bare
+/// `{"__schema": ...}` object are accepted. Built-in scalars, directives and
+/// introspection types are left out, as they would be in SDL.
+pub fn from_introspection(json: &str) -> Result<Document<'static, String>, IntrospectionError> {
+    let mut value: serde_json::Value = serde_json::from_str(json)?;
+    if let Some(data) = value.get_mut("data") {
+        value = data.take();
+    }
+    let schema = value
+        .get_mut("__schema")
+        .map(serde_json::Value::take)
+        .ok_or(IntrospectionError::MissingSchema)?;
+    let schema: IntrospectionSchema = serde_json::from_value(schema)?;
+
+    let mut definitions = Vec::new();
+
+    let query = schema.query_type.map(|t| t.name);
+    let mutation = schema.mutation_type.map(|t| t.name);
+    let subscription = schema.subscription_type.map(|t| t.name);
+    let conventional = query.as_deref().is_none_or(|n| n == "Query")
+        && mutation.as_deref().is_none_or(|n| n == "Mutation")
+        && subscription.as_deref().is_none_or(|n| n == "Subscription");
+    if !conventional {
+        definitions.push(Definition::SchemaDefinition(SchemaDefinition {
+            position: Pos::default(),
+            directives: Vec::new(),
+            query,
+            mutation,
+            subscription,
+        }));
+    }
+
+    for directive in schema.directives {
+        if BUILT_IN_DIRECTIVES.contains(&directive.name.as_str()) {
+            continue;
+        }
+        definitions.push(Definition::DirectiveDefinition(convert_directive(
+            directive,
+        )?));
+    }
+
+    for full_type in schema.types {
+        if full_type.name.starts_with("__") || BUILT_IN_SCALARS.contains(&full_type.name.as_str()) {
+            continue;
+        }
+        definitions.push(Definition::TypeDefinition(convert_type(full_type)?));
+    }
+
+    Ok(Document { definitions })
+}
+
+fn convert_type(
+    full_type: FullType,
+) -> Result<TypeDefinition<'static, String>, IntrospectionError> {
+    let FullType {
+        kind,
+        name,
+        description,
+        fields,
+        input_fields,
+        interfaces,
+        enum_values,
+        possible_types,
+        specified_by_url,
+    } = full_type;
+
+    let type_names = |refs: Option<Vec<TypeRef>>| -> Vec<String> {
+        refs.unwrap_or_default()
+            .into_iter()
+            .filter_map(|r| r.name)
+            .collect()
+    };
+
+    Ok(match kind {
+        TypeKind::Scalar => TypeDefinition::Scalar(ScalarType {
+            description,
+            directives: specified_by_url
+                .map(|url| directive("specifiedBy", vec![("url", Value::String(url))]))
+                .into_iter()
+                .collect(),
+            ..ScalarType::new(name)
+        }),
+        TypeKind::Object => TypeDefinition::Object(ObjectType {
+            description,
+            implements_interfaces: type_names(interfaces),
+            fields: convert_fields(fields)?,
+            ..ObjectType::new(name)
+        }),
+        TypeKind::Interface => TypeDefinition::Interface(InterfaceType {
+            description,
+            implements_interfaces: type_names(interfaces),
+            fields: convert_fields(fields)?,
+            ..InterfaceType::new(name)
+        }),
+        TypeKind::Union => TypeDefinition::Union(UnionType {
+            description,
+            types: type_names(possible_types),
+            ..UnionType::new(name)
+        }),
+        TypeKind::Enum => TypeDefinition::Enum(EnumType {
+            description,
+            values: enum_values
+                .unwrap_or_default()
+                .into_iter()
+                .map(|value| EnumValue {
+                    description: value.description,
+                    directives: deprecation(value.is_deprecated, value.deprecation_reason),
+                    ..EnumValue::new(value.name)
+                })
+                .collect(),
+            ..EnumType::new(name)
+        }),
+        TypeKind::InputObject => TypeDefinition::InputObject(InputObjectType {
+            description,
+            fields: input_fields
+                .unwrap_or_default()
+                .into_iter()
+                .map(convert_input_value)
+                .collect::<Result<_, _>>()?,
+            ..InputObjectType::new(name)
+        }),
+        TypeKind::List | TypeKind::NonNull => return Err(IntrospectionError::InvalidTypeRef),
+    })
+}
+
+fn convert_fields(
+    fields: Option<Vec<IntrospectionField>>,
+) -> Result<Vec<Field<'static, String>>, IntrospectionError> {
+    fields
+        .unwrap_or_default()
+        .into_iter()
+        .map(|field| {
+            Ok(Field {
+                position: Pos::default(),
+                description: field.description,
+                name: field.name,
+                arguments: field
+                    .args
+                    .into_iter()
+                    .map(convert_input_value)
+                    .collect::<Result<_, _>>()?,
+                field_type: convert_type_ref(field.field_type)?,
+                directives: deprecation(field.is_deprecated, field.deprecation_reason),
+            })
+        })
+        .collect()
+}
+
+fn convert_input_value(
+    value: IntrospectionInputValue,
+) -> Result<InputValue<'static, String>, IntrospectionError> {
+    Ok(InputValue {
+        position: Pos::default(),
+        description: value.description,
+        name: value.name,
+        value_type: convert_type_ref(value.value_type)?,
+        default_value: value.default_value.map(parse_value).transpose()?,
+        directives: deprecation(value.is_deprecated, value.deprecation_reason),
+    })
+}
+
+fn convert_type_ref(type_ref: TypeRef) -> Result<Type<'static, String>, IntrospectionError> {
+    match (type_ref.kind, type_ref.name, type_ref.of_type) {
+        (TypeKind::NonNull, _, Some(of_type)) => {
+            Ok(Type::NonNullType(Box::new(convert_type_ref(*of_type)?)))
+        }
+        (TypeKind::List, _, Some(of_type)) => {
+            Ok(Type::ListType(Box::new(convert_type_ref(*of_type)?)))
+        }
+        (_, Some(name), _) => Ok(Type::NamedType(name)),
+        _ => Err(IntrospectionError::InvalidTypeRef),
+    }
+}
+
+fn convert_directive(
+    directive: IntrospectionDirective,
+) -> Result<DirectiveDefinition<'static, String>, IntrospectionError> {
+    Ok(DirectiveDefinition {
+        position: Pos::default(),
+        description: directive.description,
+        name: directive.name,
+        arguments: directive
+            .args
+            .into_iter()
+            .map(convert_input_value)
+            .collect::<Result<_, _>>()?,
+        repeatable: directive.is_repeatable,
+        locations: directive
+            .locations
+            .into_iter()
+            .map(|location| {
+                location
+                    .parse::<DirectiveLocation>()
+                    .map_err(|_| IntrospectionError::InvalidDirectiveLocation(location))
+            })
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Default values are reported as GraphQL literals. graphql_parser has no
+/// standalone value parser, so the literal is parsed as a variable default.
+fn parse_value(literal: String) -> Result<Value<'static, String>, IntrospectionError> {
+    let source = format!("query ($v: Int = {literal}) {{ __typename }}");
+    let doc = query::parse_query::<String>(&source)
+        .map_err(|_| IntrospectionError::InvalidDefaultValue(literal.clone()))?
+        .into_static();
+
+    doc.definitions
+        .into_iter()
+        .find_map(|def| match def {
+            query::Definition::Operation(OperationDefinition::Query(q)) => q
+                .variable_definitions
+                .into_iter()
+                .next()
+                .and_then(|v| v.default_value),
+            _ => None,
+        })
+        .ok_or(IntrospectionError::InvalidDefaultValue(literal))
+}
+
+fn deprecation(is_deprecated: bool, reason: Option<String>) -> Vec<Directive<'static, String>> {
+    if !is_deprecated {
+        return Vec::new();
+    }
+    let arguments = match reason {
+        Some(reason) if reason != DEFAULT_DEPRECATION_REASON => {
+            vec![("reason", Value::String(reason))]
+        }
+        _ => Vec::new(),
+    };
+    vec![directive("deprecated", arguments)]
+}
+
+fn directive(
+    name: &str,
+    arguments: Vec<(&str, Value<'static, String>)>,
+) -> Directive<'static, String> {
+    Directive {
+        position: Pos::default(),
+        name: name.to_string(),
+        arguments: arguments
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect(),
+    }
+}
+
+/// Converts a schema document into the result of the standard
+/// `IntrospectionQuery`, shaped as `{"__schema": ...}`. Type extensions are
+/// merged into the types they extend, and the built-in scalars, directives and
+/// introspection types are included the way a server would report them.
+pub fn to_introspection(doc: &Document<'_, String>) -> serde_json::Value {
+    let built_ins = parse_schema::<String>(BUILT_IN_SDL).expect("built-in SDL is valid");
+
+    let mut schema_definition = None;
+    let mut types: Vec<TypeDefinition<'static, String>> = Vec::new();
+    let mut directives: Vec<DirectiveDefinition<'static, String>> = Vec::new();
+    let mut extensions = Vec::new();
+
+    for def in doc.clone().into_static().definitions {
+        match def {
+            Definition::SchemaDefinition(schema) => schema_definition = Some(schema),
+            Definition::TypeDefinition(td) => types.push(td),
+            Definition::TypeExtension(ext) => extensions.push(ext),
+            Definition::DirectiveDefinition(directive) => directives.push(directive),
+        }
+    }
+    for ext in extensions {
+        crate::util::apply_extension(&mut types, &ext);
+    }
+    for def in built_ins.into_static().definitions {
+        match def {
+            Definition::TypeDefinition(td) if !types.iter().any(|t| same_name(t, &td)) => {
+                types.push(td)
+            }
+            Definition::DirectiveDefinition(directive)
+                if !directives.iter().any(|d| d.name == directive.name) =>
+            {
+                directives.push(directive)
+            }
+            _ => (),
+        }
+    }
+
+    let kinds: HashMap<String, TypeKind> = types
+        .iter()
+        .map(|td| (type_name(td).clone(), type_kind(td)))
+        .collect();
+    let root = |explicit: Option<Option<String>>, conventional: &str| {
+        let name = match explicit {
+            Some(name) => name,
+            None => kinds
+                .contains_key(conventional)
+                .then(|| conventional.to_string()),
+        };
+        name.map(|name| NamedRef { name })
+    };
+
+    let schema = IntrospectionSchema {
+        query_type: root(schema_definition.as_ref().map(|s| s.query.clone()), "Query"),
+        mutation_type: root(
+            schema_definition.as_ref().map(|s| s.mutation.clone()),
+            "Mutation",
+        ),
+        subscription_type: root(
+            schema_definition.as_ref().map(|s| s.subscription.clone()),
+            "Subscription",
+        ),
+        types: types
+            .iter()
+            .map(|td| introspect_type(td, &types, &kinds))
+            .collect(),
+        directives: directives
+            .iter()
+            .map(|directive| IntrospectionDirective {
+                name: directive.name.clone(),
+                description: directive.description.clone(),
+                locations: directive
+                    .locations
+                    .iter()
+                    .map(|l| l.as_str().to_string())
+                    .collect(),
+                args: introspect_input_values(&directive.arguments, &kinds),
+                is_repeatable: directive.repeatable,
+            })
+            .collect(),
+    };
+
+    serde_json::to_value(IntrospectionResult { schema }).expect("introspection serializes")
+}
+
+fn type_name<'d>(td: &'d TypeDefinition<'static, String>) -> &'d String {
+    crate::util::schema_type_definition_name(td).expect("type definitions are named")
+}
+
+fn same_name(a: &TypeDefinition<'static, String>, b: &TypeDefinition<'static, String>) -> bool {
+    type_name(a) == type_name(b)
+}
+
+fn type_kind(td: &TypeDefinition<'_, String>) -> TypeKind {
+    match td {
+        TypeDefinition::Scalar(_) => TypeKind::Scalar,
+        TypeDefinition::Object(_) => TypeKind::Object,
+        TypeDefinition::Interface(_) => TypeKind::Interface,
+        TypeDefinition::Union(_) => TypeKind::Union,
+        TypeDefinition::Enum(_) => TypeKind::Enum,
+        TypeDefinition::InputObject(_) => TypeKind::InputObject,
+    }
+}
+
+fn introspect_type(
+    td: &TypeDefinition<'static, String>,
+    types: &[TypeDefinition<'static, String>],
+    kinds: &HashMap<String, TypeKind>,
+) -> FullType {
+    let named_refs = |names: &[String]| -> Vec<TypeRef> {
+        names
+            .iter()
+            .map(|name| named_type_ref(name, kinds))
+            .collect()
+    };
+
+    let mut full_type = FullType {
+        kind: type_kind(td),
+        name: type_name(td).clone(),
+        description: None,
+        fields: None,
+        input_fields: None,
+        interfaces: None,
+        enum_values: None,
+        possible_types: None,
+        specified_by_url: None,
+    };
+
+    match td {
+        TypeDefinition::Scalar(scalar) => {
+            full_type.description = scalar.description.clone();
+            full_type.specified_by_url = scalar
+                .directives
+                .iter()
+                .find(|d| d.name == "specifiedBy")
+                .and_then(|d| d.arguments.iter().find(|(name, _)| name == "url"))
+                .and_then(|(_, value)| match value {
+                    Value::String(url) => Some(url.clone()),
+                    _ => None,
+                });
+        }
+        TypeDefinition::Object(object) => {
+            full_type.description = object.description.clone();
+            full_type.fields = Some(introspect_fields(&object.fields, kinds));
+            full_type.interfaces = Some(named_refs(&object.implements_interfaces));
+        }
+        TypeDefinition::Interface(interface) => {
+            full_type.description = interface.description.clone();
+            full_type.fields = Some(introspect_fields(&interface.fields, kinds));
+            full_type.interfaces = Some(named_refs(&interface.implements_interfaces));
+            let implementations: Vec<String> = types
+                .iter()
+                .filter_map(|t| match t {
+                    TypeDefinition::Object(o)
+                        if o.implements_interfaces.contains(&interface.name) =>
+                    {
+                        Some(o.name.clone())
+                    }
+                    _ => None,
+                })
+                .collect();
+            full_type.possible_types = Some(named_refs(&implementations));
+        }
+        TypeDefinition::Union(union) => {
+            full_type.description = union.description.clone();
+            full_type.possible_types = Some(named_refs(&union.types));
+        }
+        TypeDefinition::Enum(enum_type) => {
+            full_type.description = enum_type.description.clone();
+            full_type.enum_values = Some(
+                enum_type
+                    .values
+                    .iter()
+                    .map(|value| {
+                        let (is_deprecated, deprecation_reason) = deprecation_of(&value.directives);
+                        IntrospectionEnumValue {
+                            name: value.name.clone(),
+                            description: value.description.clone(),
+                            is_deprecated,
+                            deprecation_reason,
+                        }
+                    })
+                    .collect(),
+            );
+        }
+        TypeDefinition::InputObject(input) => {
+            full_type.description = input.description.clone();
+            full_type.input_fields = Some(introspect_input_values(&input.fields, kinds));
+        }
+    }
+
+    full_type
+}
+
+fn introspect_fields(
+    fields: &[Field<'static, String>],
+    kinds: &HashMap<String, TypeKind>,
+) -> Vec<IntrospectionField> {
+    fields
+        .iter()
+        .map(|field| {
+            let (is_deprecated, deprecation_reason) = deprecation_of(&field.directives);
+            IntrospectionField {
+                name: field.name.clone(),
+                description: field.description.clone(),
+                args: introspect_input_values(&field.arguments, kinds),
+                field_type: introspect_type_ref(&field.field_type, kinds),
+                is_deprecated,
+                deprecation_reason,
+            }
+        })
+        .collect()
+}
+
+fn introspect_input_values(
+    values: &[InputValue<'static, String>],
+    kinds: &HashMap<String, TypeKind>,
+) -> Vec<IntrospectionInputValue> {
+    values
+        .iter()
+        .map(|value| {
+            let (is_deprecated, deprecation_reason) = deprecation_of(&value.directives);
+            IntrospectionInputValue {
+                name: value.name.clone(),
+                description: value.description.clone(),
+                value_type: introspect_type_ref(&value.value_type, kinds),
+                default_value: value.default_value.as_ref().map(|v| v.to_string()),
+                is_deprecated,
+                deprecation_reason,
+            }
+        })
+        .collect()
+}
+
+fn introspect_type_ref(ty: &Type<'static, String>, kinds: &HashMap<String, TypeKind>) -> TypeRef {
+    match ty {
+        Type::NamedType(name) => named_type_ref(name, kinds),
+        Type::ListType(inner) => TypeRef {
+            kind: TypeKind::List,
+            name: None,
+            of_type: Some(Box::new(introspect_type_ref(inner, kinds))),
+        },
+        Type::NonNullType(inner) => TypeRef {
+            kind: TypeKind::NonNull,
+            name: None,
+            of_type: Some(Box::new(introspect_type_ref(inner, kinds))),
+        },
+    }
+}
+
+/// References to undefined types are reported as scalars, the only kind that
+/// needs no further description.
+fn named_type_ref(name: &str, kinds: &HashMap<String, TypeKind>) -> TypeRef {
+    TypeRef {
+        kind: kinds.get(name).copied().unwrap_or(TypeKind::Scalar),
+        name: Some(name.to_string()),
+        of_type: None,
+    }
+}
+
+fn deprecation_of(directives: &[Directive<'static, String>]) -> (bool, Option<String>) {
+    let Some(deprecated) = directives.iter().find(|d| d.name == "deprecated") else {
+        return (false, None);
+    };
+    let reason = deprecated
+        .arguments
+        .iter()
+        .find_map(|(name, value)| match value {
+            Value::String(reason) if name == "reason" => Some(reason.clone()),
+            _ => None,
+        })
+        .unwrap_or_else(|| DEFAULT_DEPRECATION_REASON.to_string());
+    (true, Some(reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_introspection, to_introspection};
+    use graphql_parser::parse_schema;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn converts_introspection_result() {
+        let json = r#"{
+          "data": {
+            "__schema": {
+              "queryType": { "name": "Query" },
+              "mutationType": null,
+              "subscriptionType": null,
+              "types": [
+                {
+                  "kind": "OBJECT",
+                  "name": "Query",
+                  "description": null,
+                  "fields": [
+                    {
+                      "name": "users",
+                      "description": "All users",
+                      "args": [
+                        {
+                          "name": "first",
+                          "description": null,
+                          "type": { "kind": "SCALAR", "name": "Int", "ofType": null },
+                          "defaultValue": "10"
+                        },
+                        {
+                          "name": "filter",
+                          "description": null,
+                          "type": { "kind": "INPUT_OBJECT", "name": "UserFilter", "ofType": null },
+                          "defaultValue": "{role: ADMIN}"
+                        }
+                      ],
+                      "type": {
+                        "kind": "NON_NULL",
+                        "name": null,
+                        "ofType": {
+                          "kind": "LIST",
+                          "name": null,
+                          "ofType": { "kind": "OBJECT", "name": "User", "ofType": null }
+                        }
+                      },
+                      "isDeprecated": false,
+                      "deprecationReason": null
+                    }
+                  ],
+                  "inputFields": null,
+                  "interfaces": [],
+                  "enumValues": null,
+                  "possibleTypes": null
+                },
+                {
+                  "kind": "OBJECT",
+                  "name": "User",
+                  "description": null,
+                  "fields": [
+                    {
+                      "name": "id",
+                      "description": null,
+                      "args": [],
+                      "type": { "kind": "NON_NULL", "name": null, "ofType": { "kind": "SCALAR", "name": "ID", "ofType": null } },
+                      "isDeprecated": false,
+                      "deprecationReason": null
+                    },
+                    {
+                      "name": "login",
+                      "description": null,
+                      "args": [],
+                      "type": { "kind": "SCALAR", "name": "String", "ofType": null },
+                      "isDeprecated": true,
+                      "deprecationReason": "Use id"
+                    }
+                  ],
+                  "inputFields": null,
+                  "interfaces": [{ "kind": "INTERFACE", "name": "Node", "ofType": null }],
+                  "enumValues": null,
+                  "possibleTypes": null
+                },
+                {
+                  "kind": "INTERFACE",
+                  "name": "Node",
+                  "description": null,
+                  "fields": [
+                    {
+                      "name": "id",
+                      "description": null,
+                      "args": [],
+                      "type": { "kind": "NON_NULL", "name": null, "ofType": { "kind": "SCALAR", "name": "ID", "ofType": null } },
+                      "isDeprecated": false,
+                      "deprecationReason": null
+                    }
+                  ],
+                  "inputFields": null,
+                  "interfaces": [],
+                  "enumValues": null,
+                  "possibleTypes": [{ "kind": "OBJECT", "name": "User", "ofType": null }]
+                },
+                {
+                  "kind": "INPUT_OBJECT",
+                  "name": "UserFilter",
+                  "description": null,
+                  "fields": null,
+                  "inputFields": [
+                    {
+                      "name": "role",
+                      "description": null,
+                      "type": { "kind": "ENUM", "name": "Role", "ofType": null },
+                      "defaultValue": null
+                    }
+                  ],
+                  "interfaces": null,
+                  "enumValues": null,
+                  "possibleTypes": null
+                },
+                {
+                  "kind": "ENUM",
+                  "name": "Role",
+                  "description": null,
+                  "fields": null,
+                  "inputFields": null,
+                  "interfaces": null,
+                  "enumValues": [
+                    { "name": "ADMIN", "description": null, "isDeprecated": false, "deprecationReason": null },
+                    { "name": "GUEST", "description": null, "isDeprecated": true, "deprecationReason": "No longer supported" }
+                  ],
+                  "possibleTypes": null
+                },
+                {
+                  "kind": "UNION",
+                  "name": "SearchResult",
+                  "description": null,
+                  "fields": null,
+                  "inputFields": null,
+                  "interfaces": null,
+                  "enumValues": null,
+                  "possibleTypes": [{ "kind": "OBJECT", "name": "User", "ofType": null }]
+                },
+                {
+                  "kind": "SCALAR",
+                  "name": "DateTime",
+                  "description": null,
+                  "specifiedByURL": "https://example.com/datetime",
+                  "fields": null,
+                  "inputFields": null,
+                  "interfaces": null,
+                  "enumValues": null,
+                  "possibleTypes": null
+                },
+                { "kind": "SCALAR", "name": "String", "description": null },
+                { "kind": "OBJECT", "name": "__Schema", "description": null, "fields": [] }
+              ],
+              "directives": [
+                {
+                  "name": "auth",
+                  "description": null,
+                  "locations": ["FIELD_DEFINITION", "OBJECT"],
+                  "args": [
+                    {
+                      "name": "role",
+                      "description": null,
+                      "type": { "kind": "ENUM", "name": "Role", "ofType": null },
+                      "defaultValue": "ADMIN"
+                    }
+                  ],
+                  "isRepeatable": false
+                },
+                { "name": "skip", "description": null, "locations": ["FIELD"], "args": [] }
+              ]
+            }
+          }
+        }"#;
+
+        let result = from_introspection(json).unwrap();
+
+        assert_eq!(
+            result.to_string(),
+            indoc! {r#"
+                directive @auth(role: Role = ADMIN) on FIELD_DEFINITION | OBJECT
+
+                type Query {
+                  "All users"
+                  users(first: Int = 10, filter: UserFilter = {role: ADMIN}): [User]!
+                }
+
+                type User implements Node {
+                  id: ID!
+                  login: String @deprecated(reason: "Use id")
+                }
+
+                interface Node {
+                  id: ID!
+                }
+
+                input UserFilter {
+                  role: Role
+                }
+
+                enum Role {
+                  ADMIN
+                  GUEST @deprecated
+                }
+
+                union SearchResult = User
+
+                scalar DateTime @specifiedBy(url: "https://example.com/datetime")
+            "#}
+        );
+    }
+
+    #[test]
+    fn emits_schema_definition_for_unconventional_roots() {
+        let json = r#"{
+          "__schema": {
+            "queryType": { "name": "Root" },
+            "mutationType": null,
+            "subscriptionType": null,
+            "types": [
+              {
+                "kind": "OBJECT",
+                "name": "Root",
+                "fields": [
+                  {
+                    "name": "id",
+                    "args": [],
+                    "type": { "kind": "SCALAR", "name": "ID", "ofType": null }
+                  }
+                ],
+                "interfaces": []
+              }
+            ],
+            "directives": []
+          }
+        }"#;
+
+        let result = from_introspection(json).unwrap();
+
+        assert_eq!(
+            result.to_string(),
+            indoc! {"
+                schema {
+                  query: Root
+                }
+
+                type Root {
+                  id: ID
+                }
+            "}
+        );
+    }
+
+    #[test]
+    fn requires_schema() {
+        let err = from_introspection(r#"{"data": {}}"#).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "introspection result has no `__schema` field"
+        );
+    }
+
+    #[test]
+    fn round_trips_through_introspection() {
+        let sdl = indoc! {r#"
+            directive @auth(role: Role = ADMIN) repeatable on FIELD_DEFINITION | OBJECT
+
+            "The root"
+            type Query {
+              users(first: Int = 10, filter: UserFilter = {role: ADMIN}): [User!]!
+              search(term: String!): [SearchResult]
+            }
+
+            type User implements Node {
+              id: ID!
+              login: String @deprecated(reason: "Use id")
+            }
+
+            interface Node {
+              id: ID!
+            }
+
+            input UserFilter {
+              role: Role
+            }
+
+            enum Role {
+              ADMIN
+              GUEST @deprecated
+            }
+
+            union SearchResult = User
+
+            scalar DateTime @specifiedBy(url: "https://example.com/datetime")
+        "#};
+
+        let schema = parse_schema::<String>(sdl).unwrap();
+        let json = to_introspection(&schema).to_string();
+        let result = from_introspection(&json).unwrap();
+
+        assert_eq!(result.to_string(), sdl);
+    }
+
+    #[test]
+    fn introspection_includes_built_ins_and_extensions() {
+        let sdl = indoc! {"
+            type Query {
+              id: ID
+            }
+
+            interface Node {
+              id: ID!
+            }
+
+            type User {
+              id: ID!
+            }
+
+            extend type User implements Node {
+              name: String @deprecated
+            }
+        "};
+
+        let schema = parse_schema::<String>(sdl).unwrap();
+        let result = to_introspection(&schema);
+        let types = result["__schema"]["types"].as_array().unwrap();
+        let type_named = |name: &str| types.iter().find(|t| t["name"] == name).unwrap();
+
+        assert_eq!(result["__schema"]["queryType"], json!({ "name": "Query" }));
+        assert_eq!(result["__schema"]["mutationType"], json!(null));
+        assert_eq!(
+            type_named("User")["interfaces"],
+            json!([{ "kind": "INTERFACE", "name": "Node", "ofType": null }])
+        );
+        assert_eq!(
+            type_named("User")["fields"][1],
+            json!({
+                "name": "name",
+                "description": null,
+                "args": [],
+                "type": { "kind": "SCALAR", "name": "String", "ofType": null },
+                "isDeprecated": true,
+                "deprecationReason": "No longer supported"
+            })
+        );
+        assert_eq!(
+            type_named("Node")["possibleTypes"],
+            json!([{ "kind": "OBJECT", "name": "User", "ofType": null }])
+        );
+        assert_eq!(type_named("Boolean")["kind"], "SCALAR");
+        assert_eq!(type_named("__Schema")["kind"], "OBJECT");
+        assert!(result["__schema"]["directives"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|d| d["name"] == "deprecated"));
+    }
+}
@@ -0,0 +1,23 @@
+pub mod comments;
+pub mod coverage;
+pub mod dependents;
+pub mod diff;
+pub mod focus;
+pub mod graph;
+pub mod introspection;
+pub mod prune;
+pub mod sort;
+mod util;
+pub mod validate;
+
+pub use comments::Comments;
+pub use coverage::{coverage, Coverage, FieldCoverage, TypeCoverage};
+pub use dependents::{dependents, Dependents, DependentsOptions};
+pub use diff::{diff, Change, Criticality};
+pub use focus::{focus, Boundary, FocusOptions};
+pub use graph::{graph, GraphFormat, GraphOptions};
+pub use graphql_printer::PrintOptions;
+pub use introspection::{from_introspection, to_introspection, IntrospectionError};
+pub use prune::{prune, PruneError, PruneOptions};
+pub use sort::{sort, SortOptions, SortOrder};
+pub use validate::{validate, ValidationError};
@@ -0,0 +1,1367 @@
+use crate::util;
+use graphql_parser::{
+    query::{
+        Definition as QueryDef, Document as QueryDoc, FragmentDefinition, OperationDefinition,
+        Selection, SelectionSet, TypeCondition,
+    },
+    schema::{
+        Definition as SchemaDef, Directive, Document as SchemaDoc, Field, InputValue,
+        InterfaceType, InterfaceTypeExtension, ObjectType, ObjectTypeExtension, SchemaDefinition,
+        TypeDefinition, TypeExtension, UnionType, UnionTypeExtension,
+    },
+};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+
+/// Scalars every schema has without defining them.
+const BUILT_IN_SCALARS: &[&str] = &["String", "Int", "Float", "Boolean", "ID"];
+
+/// Options for [`prune`].
+#[derive(Debug, Clone, Default)]
+pub struct PruneOptions {}
+
+/// Errors returned when pruning produces a schema that is not valid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PruneError {
+    /// The pruned schema refers to types it no longer defines, or leaves a
+    /// type without fields or members. Each entry describes one problem.
+    Inconsistent(Vec<String>),
+}
+
+impl Display for PruneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PruneError::Inconsistent(problems) => {
+                write!(f, "pruned schema is inconsistent: {}", problems.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for PruneError {}
+
+/// Prunes the types and fields of the schema that are not used by the query.
+///
+/// Everything the pruned schema refers to is kept as well: the types kept
+/// fields return or accept as arguments, the types of the input fields those
+/// reference, and the directive definitions applied to kept definitions or
+/// used by the query, along with their argument types. Type extensions are
+/// pruned like the types they extend. A type kept without any selected field
+/// keeps its first field so the schema stays valid.
+pub fn prune<'a>(
+    schema_doc: &SchemaDoc<'a, String>,
+    query_doc: &QueryDoc<'_, String>,
+    _options: &PruneOptions,
+) -> Result<SchemaDoc<'a, String>, PruneError> {
+    let mut used_fields: HashMap<String, HashSet<String>> = HashMap::new();
+    for operation_fields in used_fields_by_operation(schema_doc, query_doc) {
+        for (type_name, fields) in operation_fields {
+            used_fields.entry(type_name).or_default().extend(fields);
+        }
+    }
+
+    let type_map: HashMap<&str, &TypeDefinition<String>> = schema_doc
+        .definitions
+        .iter()
+        .filter_map(|def| match def {
+            SchemaDef::TypeDefinition(td) => {
+                Some((util::schema_type_definition_name(td).unwrap().as_str(), td))
+            }
+            _ => None,
+        })
+        .collect();
+
+    let (fields, interfaces) = fields_and_interfaces(schema_doc);
+
+    // The query root is kept even when no operation selects from it, since a
+    // schema cannot do without one.
+    let mut kept: HashSet<String> = used_fields.keys().cloned().collect();
+    kept.insert(util::detect_root_types(schema_doc).query);
+    for (name, type_interfaces) in &interfaces {
+        if matches!(type_map.get(name), Some(TypeDefinition::Object(_)))
+            && type_interfaces.iter().any(|i| used_fields.contains_key(*i))
+        {
+            kept.insert(name.to_string());
+        }
+    }
+    let mut directives = query_directives(query_doc);
+
+    // Keeping a type or directive can bring in more, so the schema is pruned
+    // again until everything it refers to is kept.
+    let definitions = loop {
+        let pruner = Pruner {
+            type_map: &type_map,
+            fields: &fields,
+            interfaces: &interfaces,
+            used_fields: &used_fields,
+            kept: &kept,
+            directives: &directives,
+        };
+        let definitions: Vec<_> = schema_doc
+            .definitions
+            .iter()
+            .filter_map(|def| pruner.prune_definition(def))
+            .collect();
+
+        let mut references = References::default();
+        for def in &definitions {
+            pruner.collect_references(def, &mut references);
+        }
+        if references.types.iter().all(|name| kept.contains(name))
+            && references
+                .directives
+                .iter()
+                .all(|name| directives.contains(name))
+        {
+            break definitions;
+        }
+        kept.extend(references.types);
+        directives.extend(references.directives);
+    };
+    let pruned = SchemaDoc { definitions };
+
+    let problems = check_consistency(&pruned);
+    if problems.is_empty() {
+        Ok(pruned)
+    } else {
+        Err(PruneError::Inconsistent(problems))
+    }
+}
+
+/// The types and directives kept so far, and the fields selected on each
+/// type.
+struct Pruner<'p, 's, 'a> {
+    type_map: &'p HashMap<&'s str, &'s TypeDefinition<'a, String>>,
+    fields: &'p FieldMap<'s, 'a>,
+    interfaces: &'p HashMap<&'s str, Vec<&'s str>>,
+    used_fields: &'p HashMap<String, HashSet<String>>,
+    kept: &'p HashSet<String>,
+    directives: &'p HashSet<String>,
+}
+
+impl<'s, 'a> Pruner<'_, 's, 'a> {
+    /// The names of the fields kept on an object or interface type: those
+    /// selected on it, those kept on the interfaces it implements, or else
+    /// its first field.
+    fn kept_field_names(&self, name: &str) -> HashSet<&'s str> {
+        let fields = self.fields.get(name).map(Vec::as_slice).unwrap_or_default();
+
+        let mut names: HashSet<&str> = fields
+            .iter()
+            .map(|f| f.name.as_str())
+            .filter(|f| {
+                self.used_fields
+                    .get(name)
+                    .is_some_and(|set| set.contains(*f))
+            })
+            .collect();
+        for iface in self.interfaces.get(name).into_iter().flatten() {
+            if self.kept.contains(*iface) {
+                names.extend(self.kept_field_names(iface));
+            }
+        }
+        if names.is_empty() {
+            names.extend(fields.first().map(|f| f.name.as_str()));
+        }
+        names
+    }
+
+    fn kept_fields(&self, name: &str, fields: &[Field<'a, String>]) -> Vec<Field<'a, String>> {
+        let names = self.kept_field_names(name);
+        fields
+            .iter()
+            .filter(|f| names.contains(f.name.as_str()))
+            .cloned()
+            .collect()
+    }
+
+    /// Like [`kept_fields`](Self::kept_fields), keeping the first field of a
+    /// type definition when only fields from its extensions are kept, since
+    /// the definition cannot be left empty.
+    fn kept_definition_fields(
+        &self,
+        name: &str,
+        fields: &[Field<'a, String>],
+    ) -> Vec<Field<'a, String>> {
+        let kept = self.kept_fields(name, fields);
+        if kept.is_empty() {
+            fields.first().cloned().into_iter().collect()
+        } else {
+            kept
+        }
+    }
+
+    fn kept_names(&self, names: &[String]) -> Vec<String> {
+        names
+            .iter()
+            .filter(|name| self.kept.contains(*name))
+            .cloned()
+            .collect()
+    }
+
+    fn prune_definition(&self, def: &SchemaDef<'a, String>) -> Option<SchemaDef<'a, String>> {
+        match def {
+            SchemaDef::SchemaDefinition(schema_def) => {
+                Some(SchemaDef::SchemaDefinition(SchemaDefinition {
+                    mutation: schema_def
+                        .mutation
+                        .clone()
+                        .filter(|m| self.kept.contains(m)),
+                    subscription: schema_def
+                        .subscription
+                        .clone()
+                        .filter(|s| self.kept.contains(s)),
+                    ..schema_def.clone()
+                }))
+            }
+            SchemaDef::TypeDefinition(td) => {
+                if !self
+                    .kept
+                    .contains(util::schema_type_definition_name(td).unwrap())
+                {
+                    return None;
+                }
+                let td = match td {
+                    TypeDefinition::Object(obj) => TypeDefinition::Object(ObjectType {
+                        fields: self.kept_definition_fields(&obj.name, &obj.fields),
+                        implements_interfaces: self.kept_names(&obj.implements_interfaces),
+                        ..obj.clone()
+                    }),
+                    TypeDefinition::Interface(iface) => TypeDefinition::Interface(InterfaceType {
+                        fields: self.kept_definition_fields(&iface.name, &iface.fields),
+                        implements_interfaces: self.kept_names(&iface.implements_interfaces),
+                        ..iface.clone()
+                    }),
+                    TypeDefinition::Union(union) => TypeDefinition::Union(UnionType {
+                        types: self.kept_names(&union.types),
+                        ..union.clone()
+                    }),
+                    _ => td.clone(),
+                };
+                Some(SchemaDef::TypeDefinition(td))
+            }
+            // Extensions left with nothing to add are dropped, since
+            // `extend type` needs at least a field, interface or directive,
+            // and `extend union` a member or directive.
+            SchemaDef::TypeExtension(ext) => {
+                if !self.kept.contains(util::schema_type_extension_name(ext)) {
+                    return None;
+                }
+                let ext = match ext {
+                    TypeExtension::Object(obj) => TypeExtension::Object(ObjectTypeExtension {
+                        fields: self.kept_fields(&obj.name, &obj.fields),
+                        implements_interfaces: self.kept_names(&obj.implements_interfaces),
+                        ..obj.clone()
+                    }),
+                    TypeExtension::Interface(iface) => {
+                        TypeExtension::Interface(InterfaceTypeExtension {
+                            fields: self.kept_fields(&iface.name, &iface.fields),
+                            implements_interfaces: self.kept_names(&iface.implements_interfaces),
+                            ..iface.clone()
+                        })
+                    }
+                    TypeExtension::Union(union) => TypeExtension::Union(UnionTypeExtension {
+                        types: self.kept_names(&union.types),
+                        ..union.clone()
+                    }),
+                    _ => ext.clone(),
+                };
+                let empty = match &ext {
+                    TypeExtension::Object(obj) => {
+                        obj.fields.is_empty()
+                            && obj.implements_interfaces.is_empty()
+                            && obj.directives.is_empty()
+                    }
+                    TypeExtension::Interface(iface) => {
+                        iface.fields.is_empty()
+                            && iface.implements_interfaces.is_empty()
+                            && iface.directives.is_empty()
+                    }
+                    TypeExtension::Union(union) => {
+                        union.types.is_empty() && union.directives.is_empty()
+                    }
+                    _ => false,
+                };
+                (!empty).then_some(SchemaDef::TypeExtension(ext))
+            }
+            SchemaDef::DirectiveDefinition(directive) => self
+                .directives
+                .contains(&directive.name)
+                .then(|| def.clone()),
+        }
+    }
+
+    /// Collects the types and directives a pruned definition refers to.
+    fn collect_references(&self, def: &SchemaDef<'a, String>, references: &mut References) {
+        match def {
+            SchemaDef::SchemaDefinition(schema_def) => {
+                references.directives(&schema_def.directives);
+            }
+            SchemaDef::TypeDefinition(td) => match td {
+                TypeDefinition::Scalar(scalar) => references.directives(&scalar.directives),
+                TypeDefinition::Object(obj) => {
+                    references.directives(&obj.directives);
+                    references.fields(&obj.fields);
+                }
+                TypeDefinition::Interface(iface) => {
+                    references.directives(&iface.directives);
+                    references.fields(&iface.fields);
+                }
+                TypeDefinition::Union(union) => {
+                    references.directives(&union.directives);
+                    // A union needs at least one member, so when the query
+                    // selects on none of them they are all kept.
+                    if union.types.is_empty() {
+                        if let Some(TypeDefinition::Union(original)) =
+                            self.type_map.get(union.name.as_str())
+                        {
+                            references.types.extend(original.types.iter().cloned());
+                        }
+                    }
+                }
+                TypeDefinition::Enum(enum_type) => {
+                    references.directives(&enum_type.directives);
+                    for value in &enum_type.values {
+                        references.directives(&value.directives);
+                    }
+                }
+                TypeDefinition::InputObject(input) => {
+                    references.directives(&input.directives);
+                    references.input_values(&input.fields);
+                }
+            },
+            SchemaDef::TypeExtension(ext) => match ext {
+                TypeExtension::Scalar(scalar) => references.directives(&scalar.directives),
+                TypeExtension::Object(obj) => {
+                    references.directives(&obj.directives);
+                    references.fields(&obj.fields);
+                }
+                TypeExtension::Interface(iface) => {
+                    references.directives(&iface.directives);
+                    references.fields(&iface.fields);
+                }
+                TypeExtension::Union(union) => {
+                    references.directives(&union.directives);
+                    references.types.extend(union.types.iter().cloned());
+                }
+                TypeExtension::Enum(enum_type) => {
+                    references.directives(&enum_type.directives);
+                    for value in &enum_type.values {
+                        references.directives(&value.directives);
+                    }
+                }
+                TypeExtension::InputObject(input) => {
+                    references.directives(&input.directives);
+                    references.input_values(&input.fields);
+                }
+            },
+            SchemaDef::DirectiveDefinition(directive) => {
+                references.input_values(&directive.arguments);
+            }
+        }
+    }
+}
+
+/// The names of the types and directives a pruned schema refers to.
+#[derive(Default)]
+struct References {
+    types: Vec<String>,
+    directives: Vec<String>,
+}
+
+impl References {
+    fn directives(&mut self, directives: &[Directive<'_, String>]) {
+        self.directives
+            .extend(directives.iter().map(|d| d.name.clone()));
+    }
+
+    fn fields(&mut self, fields: &[Field<'_, String>]) {
+        for field in fields {
+            self.types
+                .push(util::named_type(&field.field_type).unwrap().clone());
+            self.directives(&field.directives);
+            self.input_values(&field.arguments);
+        }
+    }
+
+    fn input_values(&mut self, values: &[InputValue<'_, String>]) {
+        for value in values {
+            self.types
+                .push(util::named_type(&value.value_type).unwrap().clone());
+            self.directives(&value.directives);
+        }
+    }
+}
+
+/// The names of the directives used anywhere in the query.
+fn query_directives(query_doc: &QueryDoc<'_, String>) -> HashSet<String> {
+    let mut directives = HashSet::new();
+
+    for def in &query_doc.definitions {
+        let (op_directives, selection_set) = match def {
+            QueryDef::Operation(OperationDefinition::Query(q)) => (&q.directives, &q.selection_set),
+            QueryDef::Operation(OperationDefinition::Mutation(m)) => {
+                (&m.directives, &m.selection_set)
+            }
+            QueryDef::Operation(OperationDefinition::Subscription(s)) => {
+                (&s.directives, &s.selection_set)
+            }
+            QueryDef::Operation(OperationDefinition::SelectionSet(ss)) => {
+                collect_selection_directives(ss, &mut directives);
+                continue;
+            }
+            QueryDef::Fragment(f) => (&f.directives, &f.selection_set),
+        };
+        directives.extend(op_directives.iter().map(|d| d.name.clone()));
+        collect_selection_directives(selection_set, &mut directives);
+    }
+
+    directives
+}
+
+fn collect_selection_directives(
+    selection_set: &SelectionSet<'_, String>,
+    directives: &mut HashSet<String>,
+) {
+    for selection in &selection_set.items {
+        match selection {
+            Selection::Field(field) => {
+                directives.extend(field.directives.iter().map(|d| d.name.clone()));
+                collect_selection_directives(&field.selection_set, directives);
+            }
+            Selection::FragmentSpread(spread) => {
+                directives.extend(spread.directives.iter().map(|d| d.name.clone()));
+            }
+            Selection::InlineFragment(frag) => {
+                directives.extend(frag.directives.iter().map(|d| d.name.clone()));
+                collect_selection_directives(&frag.selection_set, directives);
+            }
+        }
+    }
+}
+
+/// Describes every reference of the schema to a type it does not define,
+/// every interface field an implementing type lacks and every type left
+/// without fields or members.
+fn check_consistency(doc: &SchemaDoc<'_, String>) -> Vec<String> {
+    let mut defined: HashSet<&str> = BUILT_IN_SCALARS.iter().copied().collect();
+    let mut interface_fields: HashMap<&str, Vec<&str>> = HashMap::new();
+    for def in &doc.definitions {
+        if let SchemaDef::TypeDefinition(td) = def {
+            defined.insert(util::schema_type_definition_name(td).unwrap());
+            if let TypeDefinition::Interface(iface) = td {
+                interface_fields.insert(
+                    &iface.name,
+                    iface.fields.iter().map(|f| f.name.as_str()).collect(),
+                );
+            }
+        }
+    }
+
+    let mut problems = Vec::new();
+    let mut require = |coordinate: String, name: &str| {
+        if !defined.contains(name) {
+            problems.push(format!("{coordinate} refers to undefined type {name}"));
+        }
+    };
+    let mut empty = Vec::new();
+
+    for def in &doc.definitions {
+        match def {
+            SchemaDef::SchemaDefinition(schema_def) => {
+                for root in [
+                    &schema_def.query,
+                    &schema_def.mutation,
+                    &schema_def.subscription,
+                ]
+                .into_iter()
+                .flatten()
+                {
+                    require("schema".to_string(), root);
+                }
+            }
+            SchemaDef::TypeDefinition(td) => {
+                let name = util::schema_type_definition_name(td).unwrap();
+                match td {
+                    TypeDefinition::Object(_) | TypeDefinition::Interface(_) => {
+                        let fields = type_fields(td).unwrap();
+                        if fields.is_empty() {
+                            empty.push(format!("{name} has no fields"));
+                        }
+                        require_fields(name, fields, &mut require);
+
+                        let interfaces = match td {
+                            TypeDefinition::Object(obj) => &obj.implements_interfaces,
+                            TypeDefinition::Interface(iface) => &iface.implements_interfaces,
+                            _ => unreachable!(),
+                        };
+                        for iface in interfaces {
+                            require(name.clone(), iface);
+                            for field in interface_fields.get(iface.as_str()).into_iter().flatten()
+                            {
+                                if !fields.iter().any(|f| f.name == *field) {
+                                    empty.push(format!(
+                                        "{name} implements {iface} but lacks {iface}.{field}"
+                                    ));
+                                }
+                            }
+                        }
+                    }
+                    TypeDefinition::Union(union) => {
+                        if union.types.is_empty() {
+                            empty.push(format!("{name} has no members"));
+                        }
+                        for member in &union.types {
+                            require(name.clone(), member);
+                        }
+                    }
+                    TypeDefinition::InputObject(input) => {
+                        for field in &input.fields {
+                            require(
+                                format!("{name}.{}", field.name),
+                                util::named_type(&field.value_type).unwrap(),
+                            );
+                        }
+                    }
+                    TypeDefinition::Scalar(_) | TypeDefinition::Enum(_) => (),
+                }
+            }
+            SchemaDef::TypeExtension(ext) => {
+                let name = util::schema_type_extension_name(ext);
+                require(format!("extend {name}"), name);
+                match ext {
+                    TypeExtension::Object(obj) => require_fields(name, &obj.fields, &mut require),
+                    TypeExtension::Interface(iface) => {
+                        require_fields(name, &iface.fields, &mut require)
+                    }
+                    TypeExtension::Union(union) => {
+                        for member in &union.types {
+                            require(name.clone(), member);
+                        }
+                    }
+                    TypeExtension::InputObject(input) => {
+                        for field in &input.fields {
+                            require(
+                                format!("{name}.{}", field.name),
+                                util::named_type(&field.value_type).unwrap(),
+                            );
+                        }
+                    }
+                    TypeExtension::Scalar(_) | TypeExtension::Enum(_) => (),
+                }
+            }
+            SchemaDef::DirectiveDefinition(directive) => {
+                for arg in &directive.arguments {
+                    require(
+                        format!("@{}({}:)", directive.name, arg.name),
+                        util::named_type(&arg.value_type).unwrap(),
+                    );
+                }
+            }
+        }
+    }
+
+    problems.extend(empty);
+    problems
+}
+
+fn require_fields(
+    type_name: &str,
+    fields: &[Field<'_, String>],
+    require: &mut impl FnMut(String, &str),
+) {
+    for field in fields {
+        require(
+            format!("{type_name}.{}", field.name),
+            util::named_type(&field.field_type).unwrap(),
+        );
+        for arg in &field.arguments {
+            require(
+                format!("{type_name}.{}({}:)", field.name, arg.name),
+                util::named_type(&arg.value_type).unwrap(),
+            );
+        }
+    }
+}
+
+/// The fields of each object and interface type, by type name.
+type FieldMap<'s, 'a> = HashMap<&'s str, Vec<&'s Field<'a, String>>>;
+
+/// The fields and interfaces of the object and interface types of the
+/// schema, including those added by extensions.
+fn fields_and_interfaces<'s, 'a>(
+    schema_doc: &'s SchemaDoc<'a, String>,
+) -> (FieldMap<'s, 'a>, HashMap<&'s str, Vec<&'s str>>) {
+    let mut fields: HashMap<&str, Vec<&Field<String>>> = HashMap::new();
+    let mut interfaces: HashMap<&str, Vec<&str>> = HashMap::new();
+    for def in &schema_doc.definitions {
+        let (name, type_fields, type_interfaces) = match def {
+            SchemaDef::TypeDefinition(TypeDefinition::Object(obj)) => {
+                (&obj.name, &obj.fields, &obj.implements_interfaces)
+            }
+            SchemaDef::TypeDefinition(TypeDefinition::Interface(iface)) => {
+                (&iface.name, &iface.fields, &iface.implements_interfaces)
+            }
+            SchemaDef::TypeExtension(TypeExtension::Object(obj)) => {
+                (&obj.name, &obj.fields, &obj.implements_interfaces)
+            }
+            SchemaDef::TypeExtension(TypeExtension::Interface(iface)) => {
+                (&iface.name, &iface.fields, &iface.implements_interfaces)
+            }
+            _ => continue,
+        };
+        fields.entry(name).or_default().extend(type_fields);
+        interfaces
+            .entry(name)
+            .or_default()
+            .extend(type_interfaces.iter().map(String::as_str));
+    }
+    (fields, interfaces)
+}
+
+/// Collects the fields each operation of the query selects, keyed by the
+/// name of the type they are selected on.
+pub(crate) fn used_fields_by_operation(
+    schema_doc: &SchemaDoc<'_, String>,
+    query_doc: &QueryDoc<'_, String>,
+) -> Vec<HashMap<String, HashSet<String>>> {
+    let merged = util::merged_type_definitions(schema_doc);
+    let type_map: HashMap<_, _> = merged
+        .iter()
+        .map(|td| {
+            (
+                util::schema_type_definition_name(td).unwrap().to_string(),
+                td,
+            )
+        })
+        .collect();
+
+    let (fields, _) = fields_and_interfaces(schema_doc);
+
+    let fragments: HashMap<_, _> = query_doc
+        .definitions
+        .iter()
+        .filter_map(|def| {
+            if let QueryDef::Fragment(f) = def {
+                Some((f.name.clone(), f))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let root_types = util::detect_root_types(schema_doc);
+
+    query_doc
+        .definitions
+        .iter()
+        .filter_map(|def| match def {
+            QueryDef::Operation(op) => Some(op),
+            QueryDef::Fragment(_) => None,
+        })
+        .map(|op| {
+            let (op_type, selection_set) = match op {
+                OperationDefinition::Query(q) => (root_types.query.as_str(), &q.selection_set),
+                OperationDefinition::Mutation(m) => (
+                    root_types.mutation.as_deref().unwrap_or("Mutation"),
+                    &m.selection_set,
+                ),
+                OperationDefinition::Subscription(s) => (
+                    root_types.subscription.as_deref().unwrap_or("Subscription"),
+                    &s.selection_set,
+                ),
+                OperationDefinition::SelectionSet(ss) => (root_types.query.as_str(), ss),
+            };
+
+            let mut used_fields = HashMap::new();
+            used_fields.insert(op_type.to_string(), HashSet::new());
+            collect_used_fields(
+                op_type,
+                selection_set,
+                &type_map,
+                &fields,
+                &mut used_fields,
+                &fragments,
+            );
+            used_fields
+        })
+        .collect()
+}
+
+/// Collects used fields from the selection set.
+fn collect_used_fields<'s, 'a, 'q, 'b>(
+    parent_type: &str,
+    selection_set: &SelectionSet<String>,
+    type_map: &HashMap<String, &'s TypeDefinition<'a, String>>,
+    fields_by_type: &FieldMap<'_, 'a>,
+    used_fields: &mut HashMap<String, HashSet<String>>,
+    fragments: &HashMap<String, &'q FragmentDefinition<'b, String>>,
+) {
+    if type_map.contains_key(parent_type) {
+        let fields = fields_by_type.get(parent_type);
+
+        for selection in &selection_set.items {
+            match selection {
+                Selection::Field(field) => {
+                    if let Some(schema_field) =
+                        fields.and_then(|fields| fields.iter().find(|f| f.name == field.name))
+                    {
+                        used_fields
+                            .entry(parent_type.to_string())
+                            .or_default()
+                            .insert(field.name.clone());
+
+                        let nested_type = util::named_type(&schema_field.field_type).unwrap();
+                        collect_used_fields(
+                            nested_type,
+                            &field.selection_set,
+                            type_map,
+                            fields_by_type,
+                            used_fields,
+                            fragments,
+                        );
+                    }
+                }
+                Selection::FragmentSpread(spread) => {
+                    if let Some(frag) = fragments.get(&spread.fragment_name) {
+                        let TypeCondition::On(type_condition) = &frag.type_condition;
+                        used_fields.entry(type_condition.clone()).or_default();
+                        collect_used_fields(
+                            type_condition,
+                            &frag.selection_set,
+                            type_map,
+                            fields_by_type,
+                            used_fields,
+                            fragments,
+                        );
+                    }
+                }
+                Selection::InlineFragment(frag) => {
+                    let type_name = frag
+                        .type_condition
+                        .clone()
+                        .map(|tc| match tc {
+                            TypeCondition::On(name) => name,
+                        })
+                        .unwrap_or(parent_type.to_string());
+
+                    used_fields.entry(type_name.clone()).or_default();
+                    collect_used_fields(
+                        &type_name,
+                        &frag.selection_set,
+                        type_map,
+                        fields_by_type,
+                        used_fields,
+                        fragments,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Retrieves fields for an object or interface type.
+fn type_fields<'s, 'a>(typ: &'s TypeDefinition<'a, String>) -> Option<&'s Vec<Field<'a, String>>> {
+    match typ {
+        TypeDefinition::Object(obj) => Some(&obj.fields),
+        TypeDefinition::Interface(iface) => Some(&iface.fields),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_consistency, prune, PruneOptions};
+    use graphql_parser::{parse_query, parse_schema, query::Document};
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    fn process(schema: &str, query: &str) -> String {
+        let schema = parse_schema::<String>(schema).unwrap();
+        let query = parse_query::<String>(query).unwrap();
+        prune(&schema, &query, &PruneOptions::default())
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn prunes_fields() {
+        let schema = indoc! {"
+            type Query {
+              user: User
+            }
+
+            type User {
+              id: ID!
+              name: String
+              first_name: String
+              last_name: String
+            }
+        "};
+
+        let query = indoc! {"
+            query User {
+              user {
+                id
+                name
+              }
+            }
+        "};
+
+        let result = process(schema, query);
+
+        assert_eq!(
+            result,
+            indoc! {"
+                type Query {
+                  user: User
+                }
+
+                type User {
+                  id: ID!
+                  name: String
+                }
+            "}
+        );
+    }
+
+    #[test]
+    fn prunes_interface_fields() {
+        let schema = indoc! {"
+            type Query {
+              person: Person
+            }
+
+            interface Person {
+              id: ID!
+              name: String
+              first_name: String
+              last_name: String
+            }
+
+            type User implements Person {
+              id: ID!
+              name: String
+              first_name: String
+              last_name: String
+              user_name: String
+              login: String
+            }
+
+            type Customer implements Person {
+              id: ID!
+              name: String
+              first_name: String
+              last_name: String
+              last_visited: String
+            }
+
+            type Guest implements Person {
+              id: ID!
+              name: String
+              first_name: String
+              last_name: String
+            }
+        "};
+
+        let query = indoc! {"
+            query Person {
+              person {
+                id
+                name
+                ... on User {
+                  user_name
+                }
+              }
+            }
+        "};
+
+        let result = process(schema, query);
+
+        assert_eq!(
+            result,
+            indoc! {"
+                type Query {
+                  person: Person
+                }
+
+                interface Person {
+                  id: ID!
+                  name: String
+                }
+
+                type User implements Person {
+                  id: ID!
+                  name: String
+                  user_name: String
+                }
+
+                type Customer implements Person {
+                  id: ID!
+                  name: String
+                }
+
+                type Guest implements Person {
+                  id: ID!
+                  name: String
+                }
+            "}
+        );
+    }
+
+    #[test]
+    fn combines_fields_across_documents() {
+        let schema = indoc! {"
+            type Query {
+              user: User
+              users: [User]
+            }
+
+            type User {
+              id: ID!
+              name: String
+              email: String
+            }
+        "};
+
+        let documents = [
+            indoc! {"
+                query User {
+                  user {
+                    ...UserFields
+                  }
+                }
+            "},
+            indoc! {"
+                query Users {
+                  users {
+                    email
+                  }
+                }
+
+                fragment UserFields on User {
+                  id
+                }
+            "},
+        ];
+
+        let schema = parse_schema::<String>(schema).unwrap();
+        let query = Document {
+            definitions: documents
+                .iter()
+                .flat_map(|d| parse_query::<String>(d).unwrap().definitions)
+                .collect(),
+        };
+        let result = prune(&schema, &query, &PruneOptions::default())
+            .unwrap()
+            .to_string();
+
+        assert_eq!(
+            result,
+            indoc! {"
+                type Query {
+                  user: User
+                  users: [User]
+                }
+
+                type User {
+                  id: ID!
+                  email: String
+                }
+            "}
+        );
+    }
+
+    #[test]
+    fn keeps_referenced_types() {
+        let schema = indoc! {"
+            directive @auth(requires: Role = ADMIN) on FIELD_DEFINITION
+
+            scalar DateTime
+
+            scalar Unused
+
+            type Query {
+              users(filter: UserFilter): [User] @auth
+              search(term: String!): [SearchResult]
+              node: Node
+            }
+
+            input UserFilter {
+              createdAfter: DateTime
+              status: Status
+            }
+
+            enum Status {
+              ACTIVE
+              INACTIVE
+            }
+
+            enum Role {
+              ADMIN
+              USER
+            }
+
+            interface Node {
+              id: ID!
+            }
+
+            type User implements Node {
+              id: ID!
+              name: String
+              status: Status
+            }
+
+            type Post implements Node {
+              id: ID!
+              title: String
+            }
+
+            type Comment {
+              body: String
+            }
+
+            union SearchResult = User | Post | Comment
+        "};
+
+        let query = indoc! {"
+            {
+              users(filter: {status: ACTIVE}) {
+                name
+                status
+              }
+              search(term: \"graphql\") {
+                ... on Post {
+                  title
+                }
+              }
+            }
+        "};
+
+        let result = process(schema, query);
+
+        assert_eq!(
+            result,
+            indoc! {"
+                directive @auth(requires: Role = ADMIN) on FIELD_DEFINITION
+
+                scalar DateTime
+
+                type Query {
+                  users(filter: UserFilter): [User] @auth
+                  search(term: String!): [SearchResult]
+                }
+
+                input UserFilter {
+                  createdAfter: DateTime
+                  status: Status
+                }
+
+                enum Status {
+                  ACTIVE
+                  INACTIVE
+                }
+
+                enum Role {
+                  ADMIN
+                  USER
+                }
+
+                type User {
+                  name: String
+                  status: Status
+                }
+
+                type Post {
+                  title: String
+                }
+
+                union SearchResult = User | Post
+            "}
+        );
+    }
+
+    #[test]
+    fn keeps_first_field_of_unselected_types() {
+        let schema = indoc! {"
+            type Query {
+              node: Node
+              search: SearchResult
+            }
+
+            interface Node {
+              id: ID!
+              createdAt: String
+            }
+
+            type User implements Node {
+              id: ID!
+              createdAt: String
+              name: String
+            }
+
+            type Post {
+              title: String
+            }
+
+            union SearchResult = User | Post
+        "};
+
+        let query = indoc! {"
+            {
+              node {
+                __typename
+                ... on User {
+                  name
+                }
+              }
+              search {
+                __typename
+              }
+            }
+        "};
+
+        let result = process(schema, query);
+
+        assert_eq!(
+            result,
+            indoc! {"
+                type Query {
+                  node: Node
+                  search: SearchResult
+                }
+
+                interface Node {
+                  id: ID!
+                }
+
+                type User implements Node {
+                  id: ID!
+                  name: String
+                }
+
+                union SearchResult = User
+            "}
+        );
+    }
+
+    #[test]
+    fn strips_unused_directives_and_extensions() {
+        let schema = indoc! {"
+            directive @auth(requires: Role) on FIELD_DEFINITION
+
+            directive @cached(ttl: Duration) on FIELD
+
+            directive @unused on FIELD_DEFINITION
+
+            scalar Duration
+
+            enum Role {
+              ADMIN
+            }
+
+            type Query {
+              user: User
+            }
+
+            type User {
+              id: ID!
+              email: String @auth(requires: ADMIN)
+            }
+
+            type Orphan {
+              id: ID!
+            }
+
+            extend type Query {
+              version: String
+            }
+
+            extend type User {
+              name: String
+              avatar: String @unused
+            }
+
+            extend type Orphan {
+              name: String
+            }
+        "};
+
+        let query = indoc! {"
+            {
+              user @cached(ttl: 60) {
+                email
+                name
+              }
+            }
+        "};
+
+        let result = process(schema, query);
+
+        assert_eq!(
+            result,
+            indoc! {"
+                directive @auth(requires: Role) on FIELD_DEFINITION
+
+                directive @cached(ttl: Duration) on FIELD
+
+                scalar Duration
+
+                enum Role {
+                  ADMIN
+                }
+
+                type Query {
+                  user: User
+                }
+
+                type User {
+                  email: String @auth(requires: ADMIN)
+                }
+
+                extend type User {
+                  name: String
+                }
+            "}
+        );
+    }
+
+    #[test]
+    fn keeps_a_base_field_when_only_extension_fields_are_selected() {
+        let schema = indoc! {"
+            type Query {
+              user: User
+            }
+
+            type User {
+              id: ID!
+              email: String
+            }
+
+            extend type User {
+              name: String
+              avatar: String
+            }
+        "};
+
+        let query = indoc! {"
+            {
+              user {
+                name
+              }
+            }
+        "};
+
+        let result = process(schema, query);
+
+        assert_eq!(
+            result,
+            indoc! {"
+                type Query {
+                  user: User
+                }
+
+                type User {
+                  id: ID!
+                }
+
+                extend type User {
+                  name: String
+                }
+            "}
+        );
+    }
+
+    #[test]
+    fn prunes_union_extension_members() {
+        let schema = indoc! {"
+            type Query {
+              search: [SearchResult]
+            }
+
+            union SearchResult = User | Post
+
+            extend union SearchResult = Comment
+
+            type User {
+              name: String
+            }
+
+            type Post {
+              title: String
+            }
+
+            type Comment {
+              body: String
+            }
+        "};
+
+        assert_eq!(
+            process(schema, "{ search { ... on User { name } } }"),
+            indoc! {"
+                type Query {
+                  search: [SearchResult]
+                }
+
+                union SearchResult = User
+
+                type User {
+                  name: String
+                }
+            "}
+        );
+        assert_eq!(
+            process(schema, "{ search { ... on Comment { body } } }"),
+            indoc! {"
+                type Query {
+                  search: [SearchResult]
+                }
+
+                union SearchResult = User | Post
+
+                extend union SearchResult = Comment
+
+                type User {
+                  name: String
+                }
+
+                type Post {
+                  title: String
+                }
+
+                type Comment {
+                  body: String
+                }
+            "}
+        );
+    }
+
+    #[test]
+    fn reports_inconsistent_schemas() {
+        let schema = parse_schema::<String>(indoc! {"
+            type Query {
+              user(filter: UserFilter): User
+            }
+
+            interface Node {
+              id: ID!
+            }
+
+            type User implements Node {
+              name: String
+            }
+
+            union Empty
+        "})
+        .unwrap();
+
+        assert_eq!(
+            check_consistency(&schema),
+            vec![
+                "Query.user(filter:) refers to undefined type UserFilter",
+                "User implements Node but lacks Node.id",
+                "Empty has no members",
+            ]
+        );
+    }
+}
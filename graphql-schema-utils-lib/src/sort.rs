@@ -0,0 +1,945 @@
+use crate::focus::{EdgeKind, TypeGraph};
+use crate::util;
+use graphql_parser::schema::{
+    Definition, Document, EnumValue, Field, InputValue, TypeDefinition, TypeExtension,
+};
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// How [`sort`] orders type definitions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// By name.
+    #[default]
+    Alphabetical,
+    /// Grouped by kind (scalars, enums, input types, interfaces, objects and
+    /// unions), then by name.
+    Kind,
+    /// The query, mutation and subscription types first, then the rest by
+    /// name.
+    RootsFirst,
+    /// In reading order: the query, mutation and subscription types first,
+    /// then each type after the type that first references it, breadth
+    /// first.
+    Dependency,
+}
+
+/// Options for [`sort`]. Top-level definitions are always sorted, the rest
+/// only when enabled.
+#[derive(Debug, Clone, Default)]
+pub struct SortOptions {
+    pub order: SortOrder,
+    /// Place type extensions right after the type they extend, rather than
+    /// after every type definition.
+    pub group_extensions: bool,
+    /// Sort the fields of object, interface and input types.
+    pub fields: bool,
+    /// Sort the arguments of fields and directive definitions.
+    pub arguments: bool,
+    /// Sort the values of enums.
+    pub enum_values: bool,
+    /// Sort the member types of unions.
+    pub union_members: bool,
+    /// Sort the interfaces of object and interface types.
+    pub interfaces: bool,
+}
+
+impl SortOptions {
+    /// Sorts everything inside definitions as well.
+    pub fn deep() -> Self {
+        SortOptions {
+            fields: true,
+            arguments: true,
+            enum_values: true,
+            union_members: true,
+            interfaces: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// Sorts the top-level definitions of the schema by category (schema,
+/// directives, types, extensions) and then as the order asks, and the
+/// contents of each definition as the options ask.
+pub fn sort<'a>(schema_ast: &Document<'a, String>, options: &SortOptions) -> Document<'a, String> {
+    let root_types = util::detect_root_types(schema_ast);
+    let roots = [
+        root_types.query,
+        root_types.mutation.unwrap_or("Mutation".to_string()),
+        root_types
+            .subscription
+            .unwrap_or("Subscription".to_string()),
+    ];
+    let positions = match options.order {
+        SortOrder::Dependency => dependency_order(schema_ast, &roots),
+        _ => HashMap::new(),
+    };
+    let rank = |kind: u8, name: &String| match options.order {
+        SortOrder::Alphabetical => 0,
+        SortOrder::Kind => kind as usize,
+        SortOrder::RootsFirst => roots
+            .iter()
+            .position(|root| root == name)
+            .unwrap_or(roots.len()),
+        SortOrder::Dependency => positions.get(name).copied().unwrap_or(usize::MAX),
+    };
+
+    // Extensions take the kind of the type they extend when it is defined.
+    let kinds: HashMap<&String, u8> = schema_ast
+        .definitions
+        .iter()
+        .filter_map(|def| match def {
+            Definition::TypeDefinition(td) => {
+                Some((util::schema_type_definition_name(td).unwrap(), kind(td)))
+            }
+            _ => None,
+        })
+        .collect();
+
+    // Create a vector of indices paired with sort keys
+    let mut indices_with_keys: Vec<(usize, (u8, usize, String, bool))> = schema_ast
+        .definitions
+        .iter()
+        .enumerate()
+        .map(|(i, def)| {
+            let key = match def {
+                Definition::SchemaDefinition(_) => (0, 0, String::new(), false),
+                Definition::DirectiveDefinition(dir) => (1, 0, dir.name.clone(), false),
+                Definition::TypeDefinition(td) => {
+                    let name = util::schema_type_definition_name(td).unwrap();
+                    (2, rank(kind(td), name), name.clone(), false)
+                }
+                Definition::TypeExtension(ext) => {
+                    let name = util::schema_type_extension_name(ext);
+                    let kind = kinds.get(name).copied().unwrap_or(extension_kind(ext));
+                    let category = if options.group_extensions { 2 } else { 3 };
+                    (category, rank(kind, name), name.clone(), true)
+                }
+            };
+
+            (i, key)
+        })
+        .collect();
+
+    // Sort by the keys, keeping extensions of the same type in their order
+    indices_with_keys.sort_by_key(|(_, key)| key.clone());
+
+    // Create sorted definitions using the sorted indices
+    let sorted_definitions: Vec<_> = indices_with_keys
+        .into_iter()
+        .map(|(i, _)| sort_definition(schema_ast.definitions[i].clone(), options))
+        .collect();
+
+    // Create a new document with sorted definitions
+    Document {
+        definitions: sorted_definitions,
+    }
+}
+
+/// Numbers the types in the order a breadth-first walk of the type graph
+/// [`focus`](crate::focus) builds reaches them, following references in the
+/// order they appear. Implemented interfaces are followed too, before the
+/// fields, so an interface comes before the types implementing it. The walk
+/// starts from all of the root types at once, then from each type it has not
+/// reached yet in definition order.
+fn dependency_order<'s>(
+    schema_ast: &'s Document<'_, String>,
+    roots: &'s [String],
+) -> HashMap<&'s String, usize> {
+    let TypeGraph { graph, nodes, .. } = TypeGraph::new(schema_ast);
+
+    let others = schema_ast.definitions.iter().filter_map(|def| match def {
+        Definition::TypeDefinition(td) => util::schema_type_definition_name(td),
+        Definition::TypeExtension(ext) => Some(util::schema_type_extension_name(ext)),
+        _ => None,
+    });
+    let starts = std::iter::once(roots.iter().collect::<Vec<_>>()).chain(others.map(|t| vec![t]));
+
+    let mut positions = HashMap::new();
+    let mut seen = HashSet::new();
+    for start in starts {
+        let mut queue: VecDeque<_> = start
+            .into_iter()
+            .filter_map(|t| nodes.get(t).copied())
+            .filter(|idx| seen.insert(*idx))
+            .collect();
+
+        while let Some(idx) = queue.pop_front() {
+            positions.insert(graph[idx], positions.len());
+
+            let mut interfaces: Vec<_> = graph
+                .edges_directed(idx, Direction::Incoming)
+                .filter(|edge| edge.weight().kind == EdgeKind::Implements)
+                .collect();
+            interfaces.sort_by_key(|edge| edge.id());
+            let mut edges: Vec<_> = graph.edges(idx).collect();
+            edges.sort_by_key(|edge| edge.id());
+            let next = interfaces
+                .into_iter()
+                .map(|edge| edge.source())
+                .chain(edges.into_iter().map(|edge| edge.target()));
+            for next in next {
+                if seen.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    positions
+}
+
+/// The position of a type's kind when sorting by [`SortOrder::Kind`].
+fn kind(td: &TypeDefinition<'_, String>) -> u8 {
+    match td {
+        TypeDefinition::Scalar(_) => 0,
+        TypeDefinition::Enum(_) => 1,
+        TypeDefinition::InputObject(_) => 2,
+        TypeDefinition::Interface(_) => 3,
+        TypeDefinition::Object(_) => 4,
+        TypeDefinition::Union(_) => 5,
+    }
+}
+
+fn extension_kind(ext: &TypeExtension<'_, String>) -> u8 {
+    match ext {
+        TypeExtension::Scalar(_) => 0,
+        TypeExtension::Enum(_) => 1,
+        TypeExtension::InputObject(_) => 2,
+        TypeExtension::Interface(_) => 3,
+        TypeExtension::Object(_) => 4,
+        TypeExtension::Union(_) => 5,
+    }
+}
+
+/// Sorts the contents of a definition.
+fn sort_definition<'a>(
+    mut def: Definition<'a, String>,
+    options: &SortOptions,
+) -> Definition<'a, String> {
+    match &mut def {
+        Definition::SchemaDefinition(_) => (),
+        Definition::DirectiveDefinition(directive) => {
+            sort_input_values(&mut directive.arguments, options.arguments);
+        }
+        Definition::TypeDefinition(td) => match td {
+            TypeDefinition::Scalar(_) => (),
+            TypeDefinition::Object(obj) => {
+                sort_fields(&mut obj.fields, options);
+                sort_names(&mut obj.implements_interfaces, options.interfaces);
+            }
+            TypeDefinition::Interface(iface) => {
+                sort_fields(&mut iface.fields, options);
+                sort_names(&mut iface.implements_interfaces, options.interfaces);
+            }
+            TypeDefinition::Union(union) => sort_names(&mut union.types, options.union_members),
+            TypeDefinition::Enum(enum_type) => {
+                sort_enum_values(&mut enum_type.values, options.enum_values);
+            }
+            TypeDefinition::InputObject(input) => {
+                sort_input_values(&mut input.fields, options.fields);
+            }
+        },
+        Definition::TypeExtension(ext) => match ext {
+            TypeExtension::Scalar(_) => (),
+            TypeExtension::Object(obj) => {
+                sort_fields(&mut obj.fields, options);
+                sort_names(&mut obj.implements_interfaces, options.interfaces);
+            }
+            TypeExtension::Interface(iface) => {
+                sort_fields(&mut iface.fields, options);
+                sort_names(&mut iface.implements_interfaces, options.interfaces);
+            }
+            TypeExtension::Union(union) => sort_names(&mut union.types, options.union_members),
+            TypeExtension::Enum(enum_type) => {
+                sort_enum_values(&mut enum_type.values, options.enum_values);
+            }
+            TypeExtension::InputObject(input) => {
+                sort_input_values(&mut input.fields, options.fields);
+            }
+        },
+    }
+    def
+}
+
+fn sort_fields(fields: &mut [Field<'_, String>], options: &SortOptions) {
+    if options.fields {
+        fields.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+    for field in fields {
+        sort_input_values(&mut field.arguments, options.arguments);
+    }
+}
+
+fn sort_input_values(values: &mut [InputValue<'_, String>], enabled: bool) {
+    if enabled {
+        values.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+}
+
+fn sort_enum_values(values: &mut [EnumValue<'_, String>], enabled: bool) {
+    if enabled {
+        values.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+}
+
+fn sort_names(names: &mut [String], enabled: bool) {
+    if enabled {
+        names.sort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sort, SortOptions, SortOrder};
+    use graphql_parser::parse_schema;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    fn process(schema: &str) -> String {
+        let schema = parse_schema::<String>(schema).unwrap();
+        sort(&schema, &SortOptions::default()).to_string()
+    }
+
+    #[test]
+    fn test_sort_basic_types() {
+        let schema = indoc! {"
+            type User {
+              id: ID!
+              name: String
+            }
+
+            type Query {
+              user: User
+            }
+
+            type Company {
+              id: ID!
+              name: String
+            }
+        "};
+
+        let result = process(schema);
+        let expected_schema = indoc! {"
+            type Company {
+              id: ID!
+              name: String
+            }
+
+            type Query {
+              user: User
+            }
+
+            type User {
+              id: ID!
+              name: String
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_sort_mixed_definitions() {
+        let schema = indoc! {"
+            type User {
+              id: ID!
+              name: String
+            }
+
+            enum Status {
+              ACTIVE
+              INACTIVE
+            }
+
+            interface Node {
+              id: ID!
+            }
+
+            type Query {
+              user: User
+            }
+
+            scalar DateTime
+
+            union SearchResult = User | Company
+
+            type Company {
+              id: ID!
+              name: String
+            }
+        "};
+
+        let result = process(schema);
+        let expected_schema = indoc! {"
+            type Company {
+              id: ID!
+              name: String
+            }
+
+            scalar DateTime
+
+            interface Node {
+              id: ID!
+            }
+
+            type Query {
+              user: User
+            }
+
+            union SearchResult = User | Company
+
+            enum Status {
+              ACTIVE
+              INACTIVE
+            }
+
+            type User {
+              id: ID!
+              name: String
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_sort_with_schema_definition() {
+        let schema = indoc! {"
+            type User {
+              id: ID!
+              name: String
+            }
+
+            schema {
+              query: Query
+              mutation: Mutation
+            }
+
+            type Query {
+              user: User
+            }
+
+            type Mutation {
+              createUser(name: String!): User
+            }
+        "};
+
+        let result = process(schema);
+        let expected_schema = indoc! {"
+            schema {
+              query: Query
+              mutation: Mutation
+            }
+
+            type Mutation {
+              createUser(name: String!): User
+            }
+
+            type Query {
+              user: User
+            }
+
+            type User {
+              id: ID!
+              name: String
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_sort_with_directives() {
+        let schema = indoc! {"
+            type User {
+              id: ID!
+              name: String
+            }
+
+            directive @deprecated(reason: String) on FIELD_DEFINITION
+
+            directive @auth(role: String!) on FIELD_DEFINITION
+
+            type Query {
+              user: User
+            }
+        "};
+
+        let result = process(schema);
+        let expected_schema = indoc! {"
+            directive @auth(role: String!) on FIELD_DEFINITION
+
+            directive @deprecated(reason: String) on FIELD_DEFINITION
+
+            type Query {
+              user: User
+            }
+
+            type User {
+              id: ID!
+              name: String
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_sort_input_types() {
+        let schema = indoc! {"
+            type User {
+              id: ID!
+              name: String
+            }
+
+            input UserInput {
+              name: String!
+            }
+
+            input CreateUserInput {
+              user: UserInput!
+            }
+
+            type Query {
+              user: User
+            }
+        "};
+
+        let result = process(schema);
+        let expected_schema = indoc! {"
+            input CreateUserInput {
+              user: UserInput!
+            }
+
+            type Query {
+              user: User
+            }
+
+            type User {
+              id: ID!
+              name: String
+            }
+
+            input UserInput {
+              name: String!
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_sort_empty_schema() {
+        // GraphQL parser doesn't accept completely empty schemas
+        // Use a minimal valid schema instead
+        let schema = "type Query { id: ID }";
+        let result = process(schema);
+        let expected = "type Query {\n  id: ID\n}";
+        assert_eq!(result.trim(), expected.trim());
+    }
+
+    #[test]
+    fn test_sort_single_type() {
+        let schema = indoc! {"
+            type User {
+              id: ID!
+              name: String
+            }
+        "};
+
+        let result = process(schema);
+        let expected_schema = indoc! {"
+            type User {
+              id: ID!
+              name: String
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_sort_deep() {
+        let schema = indoc! {"
+            type User implements Node & Entity {
+              name: String
+              posts(last: Int, first: Int): [Post]
+              id: ID!
+            }
+
+            enum Status {
+              INACTIVE
+              ACTIVE
+            }
+
+            union SearchResult = User | Post
+
+            input UserInput {
+              name: String
+              email: String
+            }
+
+            directive @limit(min: Int, max: Int) on FIELD_DEFINITION
+        "};
+
+        let schema = parse_schema::<String>(schema).unwrap();
+        let result = sort(&schema, &SortOptions::deep()).to_string();
+        let expected_schema = indoc! {"
+            directive @limit(max: Int, min: Int) on FIELD_DEFINITION
+
+            union SearchResult = Post | User
+
+            enum Status {
+              ACTIVE
+              INACTIVE
+            }
+
+            type User implements Entity & Node {
+              id: ID!
+              name: String
+              posts(first: Int, last: Int): [Post]
+            }
+
+            input UserInput {
+              email: String
+              name: String
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_sort_fields_only() {
+        let schema = indoc! {"
+            type User {
+              name: String
+              posts(last: Int, first: Int): [Post]
+              id: ID!
+            }
+        "};
+
+        let schema = parse_schema::<String>(schema).unwrap();
+        let options = SortOptions {
+            fields: true,
+            ..Default::default()
+        };
+        let result = sort(&schema, &options).to_string();
+        let expected_schema = indoc! {"
+            type User {
+              id: ID!
+              name: String
+              posts(last: Int, first: Int): [Post]
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_sort_extensions_by_name() {
+        let schema = indoc! {"
+            extend type User {
+              email: String
+            }
+
+            type User {
+              id: ID!
+            }
+
+            extend type Query {
+              me: User
+            }
+
+            type Query {
+              user: User
+            }
+        "};
+
+        let result = process(schema);
+        let expected_schema = indoc! {"
+            type Query {
+              user: User
+            }
+
+            type User {
+              id: ID!
+            }
+
+            extend type Query {
+              me: User
+            }
+
+            extend type User {
+              email: String
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_sort_by_kind_with_grouped_extensions() {
+        let schema = indoc! {"
+            extend enum Status {
+              ARCHIVED
+            }
+
+            type User {
+              status: Status
+            }
+
+            union SearchResult = User
+
+            extend type User {
+              email: String
+            }
+
+            enum Status {
+              ACTIVE
+            }
+
+            input UserInput {
+              name: String
+            }
+
+            scalar DateTime
+
+            interface Node {
+              id: ID!
+            }
+        "};
+
+        let schema = parse_schema::<String>(schema).unwrap();
+        let options = SortOptions {
+            order: SortOrder::Kind,
+            group_extensions: true,
+            ..Default::default()
+        };
+        let result = sort(&schema, &options).to_string();
+        let expected_schema = indoc! {"
+            scalar DateTime
+
+            enum Status {
+              ACTIVE
+            }
+
+            extend enum Status {
+              ARCHIVED
+            }
+
+            input UserInput {
+              name: String
+            }
+
+            interface Node {
+              id: ID!
+            }
+
+            type User {
+              status: Status
+            }
+
+            extend type User {
+              email: String
+            }
+
+            union SearchResult = User
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_sort_roots_first() {
+        let schema = indoc! {"
+            type Account {
+              id: ID!
+            }
+
+            type RootSubscription {
+              accountChanged: Account
+            }
+
+            type RootQuery {
+              account: Account
+            }
+
+            type Mutation {
+              closeAccount: Account
+            }
+
+            schema {
+              query: RootQuery
+              subscription: RootSubscription
+            }
+        "};
+
+        let schema = parse_schema::<String>(schema).unwrap();
+        let options = SortOptions {
+            order: SortOrder::RootsFirst,
+            ..Default::default()
+        };
+        let result = sort(&schema, &options).to_string();
+        let expected_schema = indoc! {"
+            schema {
+              query: RootQuery
+              subscription: RootSubscription
+            }
+
+            type RootQuery {
+              account: Account
+            }
+
+            type Mutation {
+              closeAccount: Account
+            }
+
+            type RootSubscription {
+              accountChanged: Account
+            }
+
+            type Account {
+              id: ID!
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_sort_dependency_order() {
+        let schema = indoc! {"
+            type Comment {
+              author: User
+            }
+
+            scalar DateTime
+
+            type Post {
+              comments: [Comment]
+              publishedAt: DateTime
+            }
+
+            type Mutation {
+              createPost(input: PostInput): Post
+            }
+
+            type Unused {
+              id: ID!
+            }
+
+            input PostInput {
+              title: String
+            }
+
+            type User {
+              posts: [Post]
+            }
+
+            type Query {
+              me: User
+            }
+        "};
+
+        let schema = parse_schema::<String>(schema).unwrap();
+        let options = SortOptions {
+            order: SortOrder::Dependency,
+            ..Default::default()
+        };
+        let result = sort(&schema, &options).to_string();
+        let expected_schema = indoc! {"
+            type Query {
+              me: User
+            }
+
+            type Mutation {
+              createPost(input: PostInput): Post
+            }
+
+            type User {
+              posts: [Post]
+            }
+
+            type Post {
+              comments: [Comment]
+              publishedAt: DateTime
+            }
+
+            input PostInput {
+              title: String
+            }
+
+            type Comment {
+              author: User
+            }
+
+            scalar DateTime
+
+            type Unused {
+              id: ID!
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+
+    #[test]
+    fn test_sort_dependency_order_with_interfaces() {
+        let schema = indoc! {"
+            type Post implements Node & Entity {
+              id: ID!
+              author: User
+            }
+
+            interface Entity {
+              id: ID!
+            }
+
+            type User implements Node {
+              id: ID!
+            }
+
+            interface Node {
+              id: ID!
+            }
+
+            type Query {
+              post: Post
+            }
+        "};
+
+        let schema = parse_schema::<String>(schema).unwrap();
+        let options = SortOptions {
+            order: SortOrder::Dependency,
+            ..Default::default()
+        };
+        let result = sort(&schema, &options).to_string();
+        let expected_schema = indoc! {"
+            type Query {
+              post: Post
+            }
+
+            type Post implements Node & Entity {
+              id: ID!
+              author: User
+            }
+
+            interface Node {
+              id: ID!
+            }
+
+            interface Entity {
+              id: ID!
+            }
+
+            type User implements Node {
+              id: ID!
+            }
+        "};
+
+        assert_eq!(result.trim(), expected_schema.trim());
+    }
+}
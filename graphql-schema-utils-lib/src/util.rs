@@ -0,0 +1,163 @@
+use graphql_parser::query::Text;
+use graphql_parser::schema::{
+    Definition, Document, EnumType, InputObjectType, InterfaceType, ObjectType, ScalarType, Type,
+    TypeDefinition, TypeExtension, UnionType,
+};
+
+pub fn schema_definition_name<'d, 'a, V, D: Text<'a, Value = V>>(
+    d: &'d Definition<'a, D>,
+) -> Option<&'d V> {
+    match d {
+        Definition::SchemaDefinition(_) => None,
+        Definition::TypeDefinition(type_definition) => schema_type_definition_name(type_definition),
+        Definition::TypeExtension(_) => None,
+        Definition::DirectiveDefinition(directive_definition) => Some(&directive_definition.name),
+    }
+}
+
+pub fn schema_type_definition_name<'d, 'a, V, D: Text<'a, Value = V>>(
+    td: &'d TypeDefinition<'a, D>,
+) -> Option<&'d V> {
+    match td {
+        TypeDefinition::Scalar(scalar_type) => Some(&scalar_type.name),
+        TypeDefinition::Object(object_type) => Some(&object_type.name),
+        TypeDefinition::Interface(interface_type) => Some(&interface_type.name),
+        TypeDefinition::Union(union_type) => Some(&union_type.name),
+        TypeDefinition::Enum(enum_type) => Some(&enum_type.name),
+        TypeDefinition::InputObject(input_object_type) => Some(&input_object_type.name),
+    }
+}
+
+pub fn named_type<'d, 'a, V, D: Text<'a, Value = V>>(ty: &'d Type<'a, D>) -> Option<&'d V> {
+    match ty {
+        Type::NamedType(n) => Some(n),
+        Type::ListType(inner) | Type::NonNullType(inner) => named_type(inner),
+    }
+}
+
+pub fn schema_type_extension_name<'d, 'a, V, D: Text<'a, Value = V>>(
+    ext: &'d TypeExtension<'a, D>,
+) -> &'d V {
+    match ext {
+        TypeExtension::Scalar(scalar_type) => &scalar_type.name,
+        TypeExtension::Object(object_type) => &object_type.name,
+        TypeExtension::Interface(interface_type) => &interface_type.name,
+        TypeExtension::Union(union_type) => &union_type.name,
+        TypeExtension::Enum(enum_type) => &enum_type.name,
+        TypeExtension::InputObject(input_object_type) => &input_object_type.name,
+    }
+}
+
+/// Detects root types (Query, Mutation, Subscription) from the schema.
+pub fn detect_root_types(schema: &Document<String>) -> RootTypes {
+    let mut root = RootTypes {
+        query: "Query".to_string(),
+        mutation: None,
+        subscription: None,
+    };
+
+    for def in &schema.definitions {
+        if let Definition::SchemaDefinition(schema_def) = def {
+            if let Some(query) = &schema_def.query {
+                root.query = query.clone();
+            }
+            if let Some(mutation) = &schema_def.mutation {
+                root.mutation = Some(mutation.clone());
+            }
+            if let Some(subscription) = &schema_def.subscription {
+                root.subscription = Some(subscription.clone());
+            }
+        }
+    }
+
+    root
+}
+
+pub struct RootTypes {
+    pub query: String,
+    pub mutation: Option<String>,
+    pub subscription: Option<String>,
+}
+
+/// The type definitions of the schema with each type extension merged into
+/// the type it extends. Extensions of types the schema does not define stand
+/// in for them, as if the first one were a definition.
+pub fn merged_type_definitions<'a>(
+    schema: &Document<'a, String>,
+) -> Vec<TypeDefinition<'a, String>> {
+    let mut types: Vec<TypeDefinition<'a, String>> = schema
+        .definitions
+        .iter()
+        .filter_map(|def| match def {
+            Definition::TypeDefinition(td) => Some(td.clone()),
+            _ => None,
+        })
+        .collect();
+
+    for def in &schema.definitions {
+        let Definition::TypeExtension(ext) = def else {
+            continue;
+        };
+        let name = schema_type_extension_name(ext);
+        if !types
+            .iter()
+            .any(|td| schema_type_definition_name(td) == Some(name))
+        {
+            types.push(match ext {
+                TypeExtension::Scalar(_) => TypeDefinition::Scalar(ScalarType::new(name.clone())),
+                TypeExtension::Object(_) => TypeDefinition::Object(ObjectType::new(name.clone())),
+                TypeExtension::Interface(_) => {
+                    TypeDefinition::Interface(InterfaceType::new(name.clone()))
+                }
+                TypeExtension::Union(_) => TypeDefinition::Union(UnionType::new(name.clone())),
+                TypeExtension::Enum(_) => TypeDefinition::Enum(EnumType::new(name.clone())),
+                TypeExtension::InputObject(_) => {
+                    TypeDefinition::InputObject(InputObjectType::new(name.clone()))
+                }
+            });
+        }
+        apply_extension(&mut types, ext);
+    }
+
+    types
+}
+
+/// Adds the directives, interfaces, fields, members and values of the
+/// extension to the type of the same name and kind.
+pub fn apply_extension<'a>(
+    types: &mut [TypeDefinition<'a, String>],
+    ext: &TypeExtension<'a, String>,
+) {
+    for td in types.iter_mut() {
+        match (td, ext) {
+            (TypeDefinition::Scalar(t), TypeExtension::Scalar(e)) if t.name == e.name => {
+                t.directives.extend(e.directives.clone());
+            }
+            (TypeDefinition::Object(t), TypeExtension::Object(e)) if t.name == e.name => {
+                t.implements_interfaces
+                    .extend(e.implements_interfaces.clone());
+                t.directives.extend(e.directives.clone());
+                t.fields.extend(e.fields.clone());
+            }
+            (TypeDefinition::Interface(t), TypeExtension::Interface(e)) if t.name == e.name => {
+                t.implements_interfaces
+                    .extend(e.implements_interfaces.clone());
+                t.directives.extend(e.directives.clone());
+                t.fields.extend(e.fields.clone());
+            }
+            (TypeDefinition::Union(t), TypeExtension::Union(e)) if t.name == e.name => {
+                t.directives.extend(e.directives.clone());
+                t.types.extend(e.types.clone());
+            }
+            (TypeDefinition::Enum(t), TypeExtension::Enum(e)) if t.name == e.name => {
+                t.directives.extend(e.directives.clone());
+                t.values.extend(e.values.clone());
+            }
+            (TypeDefinition::InputObject(t), TypeExtension::InputObject(e)) if t.name == e.name => {
+                t.directives.extend(e.directives.clone());
+                t.fields.extend(e.fields.clone());
+            }
+            _ => (),
+        }
+    }
+}
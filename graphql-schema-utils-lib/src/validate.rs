@@ -0,0 +1,1628 @@
+use crate::introspection::BUILT_IN_SDL;
+use crate::util;
+use graphql_parser::query::{
+    Definition as QueryDef, Directive, Document as QueryDoc, Field as QueryField,
+    FragmentDefinition, OperationDefinition, Selection, SelectionSet, TypeCondition, Value,
+    VariableDefinition,
+};
+use graphql_parser::schema::{
+    parse_schema, Definition as SchemaDef, DirectiveDefinition, DirectiveLocation,
+    Document as SchemaDoc, Field, InputValue, Type, TypeDefinition, TypeExtension,
+};
+use graphql_parser::Pos;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+
+/// A violation of one of the validation rules of the GraphQL specification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub message: String,
+    /// The positions in the query document the error refers to, such as both
+    /// fields of a conflicting pair.
+    pub locations: Vec<Pos>,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.locations.first() {
+            Some(pos) => write!(f, "{pos}: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// Validates the query against the schema using the validation rules of the
+/// GraphQL specification. Every error found is returned, ordered by position;
+/// an empty result means the query is valid.
+pub fn validate(
+    schema_doc: &SchemaDoc<'_, String>,
+    query_doc: &QueryDoc<'_, String>,
+) -> Vec<ValidationError> {
+    let schema = SchemaIndex::new(schema_doc);
+    let query = query_doc.clone().into_static();
+
+    let mut validator = Validator {
+        schema: &schema,
+        fragments: HashMap::new(),
+        errors: Vec::new(),
+    };
+    validator.validate_document(&query);
+
+    let mut errors = validator.errors;
+    errors.sort_by_key(|e| e.locations.first().copied());
+    let mut unique: Vec<ValidationError> = Vec::with_capacity(errors.len());
+    for error in errors {
+        if !unique.contains(&error) {
+            unique.push(error);
+        }
+    }
+    unique
+}
+
+/// The fields every type or the query root can select without declaring them.
+const META_FIELDS_SDL: &str = "
+type __MetaFields {
+  __typename: String!
+  __schema: __Schema!
+  __type(name: String!): __Type
+}
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Scalar,
+    Object,
+    Interface,
+    Union,
+    Enum,
+    InputObject,
+}
+
+/// A type definition with its extensions merged in.
+struct TypeInfo {
+    kind: Kind,
+    fields: Vec<Field<'static, String>>,
+    input_fields: Vec<InputValue<'static, String>>,
+    interfaces: Vec<String>,
+    members: Vec<String>,
+    values: Vec<String>,
+}
+
+/// The schema together with the built-in scalars, directives and
+/// introspection types, indexed by name.
+struct SchemaIndex {
+    types: HashMap<String, TypeInfo>,
+    directives: HashMap<String, DirectiveDefinition<'static, String>>,
+    query: Option<String>,
+    mutation: Option<String>,
+    subscription: Option<String>,
+    meta_fields: Vec<Field<'static, String>>,
+}
+
+impl SchemaIndex {
+    fn new(doc: &SchemaDoc<'_, String>) -> Self {
+        let mut index = SchemaIndex {
+            types: HashMap::new(),
+            directives: HashMap::new(),
+            query: None,
+            mutation: None,
+            subscription: None,
+            meta_fields: Vec::new(),
+        };
+
+        let mut schema_definition = None;
+        let mut extensions = Vec::new();
+        for def in doc.clone().into_static().definitions {
+            match def {
+                SchemaDef::SchemaDefinition(schema) => schema_definition = Some(schema),
+                SchemaDef::TypeDefinition(td) => index.add_type(td),
+                SchemaDef::TypeExtension(ext) => extensions.push(ext),
+                SchemaDef::DirectiveDefinition(directive) => {
+                    index.directives.insert(directive.name.clone(), directive);
+                }
+            }
+        }
+        for ext in extensions {
+            index.extend_type(ext);
+        }
+
+        let built_ins = parse_schema::<String>(BUILT_IN_SDL).expect("built-in SDL is valid");
+        for def in built_ins.into_static().definitions {
+            match def {
+                SchemaDef::TypeDefinition(td)
+                    if !index
+                        .types
+                        .contains_key(util::schema_type_definition_name(&td).unwrap()) =>
+                {
+                    index.add_type(td)
+                }
+                SchemaDef::DirectiveDefinition(directive) => {
+                    index
+                        .directives
+                        .entry(directive.name.clone())
+                        .or_insert(directive);
+                }
+                _ => (),
+            }
+        }
+
+        let meta = parse_schema::<String>(META_FIELDS_SDL).expect("meta field SDL is valid");
+        if let Some(SchemaDef::TypeDefinition(TypeDefinition::Object(object))) =
+            meta.into_static().definitions.into_iter().next()
+        {
+            index.meta_fields = object.fields;
+        }
+
+        let conventional = |name: &str| index.types.contains_key(name).then(|| name.to_string());
+        let (query, mutation, subscription) = match schema_definition {
+            Some(schema) => (schema.query, schema.mutation, schema.subscription),
+            None => (
+                conventional("Query"),
+                conventional("Mutation"),
+                conventional("Subscription"),
+            ),
+        };
+        index.query = query;
+        index.mutation = mutation;
+        index.subscription = subscription;
+
+        index
+    }
+
+    fn add_type(&mut self, td: TypeDefinition<'static, String>) {
+        let mut info = TypeInfo {
+            kind: Kind::Scalar,
+            fields: Vec::new(),
+            input_fields: Vec::new(),
+            interfaces: Vec::new(),
+            members: Vec::new(),
+            values: Vec::new(),
+        };
+        let name = match td {
+            TypeDefinition::Scalar(scalar) => scalar.name,
+            TypeDefinition::Object(object) => {
+                info.kind = Kind::Object;
+                info.fields = object.fields;
+                info.interfaces = object.implements_interfaces;
+                object.name
+            }
+            TypeDefinition::Interface(interface) => {
+                info.kind = Kind::Interface;
+                info.fields = interface.fields;
+                info.interfaces = interface.implements_interfaces;
+                interface.name
+            }
+            TypeDefinition::Union(union) => {
+                info.kind = Kind::Union;
+                info.members = union.types;
+                union.name
+            }
+            TypeDefinition::Enum(enum_type) => {
+                info.kind = Kind::Enum;
+                info.values = enum_type.values.into_iter().map(|v| v.name).collect();
+                enum_type.name
+            }
+            TypeDefinition::InputObject(input) => {
+                info.kind = Kind::InputObject;
+                info.input_fields = input.fields;
+                input.name
+            }
+        };
+        self.types.insert(name, info);
+    }
+
+    fn extend_type(&mut self, ext: TypeExtension<'static, String>) {
+        match ext {
+            TypeExtension::Scalar(_) => (),
+            TypeExtension::Object(object) => {
+                if let Some(info) = self.types.get_mut(&object.name) {
+                    info.fields.extend(object.fields);
+                    info.interfaces.extend(object.implements_interfaces);
+                }
+            }
+            TypeExtension::Interface(interface) => {
+                if let Some(info) = self.types.get_mut(&interface.name) {
+                    info.fields.extend(interface.fields);
+                    info.interfaces.extend(interface.implements_interfaces);
+                }
+            }
+            TypeExtension::Union(union) => {
+                if let Some(info) = self.types.get_mut(&union.name) {
+                    info.members.extend(union.types);
+                }
+            }
+            TypeExtension::Enum(enum_type) => {
+                if let Some(info) = self.types.get_mut(&enum_type.name) {
+                    info.values
+                        .extend(enum_type.values.into_iter().map(|v| v.name));
+                }
+            }
+            TypeExtension::InputObject(input) => {
+                if let Some(info) = self.types.get_mut(&input.name) {
+                    info.input_fields.extend(input.fields);
+                }
+            }
+        }
+    }
+
+    fn kind(&self, name: &str) -> Option<Kind> {
+        self.types.get(name).map(|info| info.kind)
+    }
+
+    fn is_composite(&self, name: &str) -> bool {
+        matches!(
+            self.kind(name),
+            Some(Kind::Object | Kind::Interface | Kind::Union)
+        )
+    }
+
+    fn is_leaf(&self, name: &str) -> bool {
+        matches!(self.kind(name), Some(Kind::Scalar | Kind::Enum))
+    }
+
+    /// Looks up the definition of a field selected on `parent`, including the
+    /// meta fields.
+    fn field(&self, parent: &str, name: &str) -> Option<&Field<'static, String>> {
+        let meta = |name: &str| self.meta_fields.iter().find(|f| f.name == name);
+        if name == "__typename" {
+            return meta(name);
+        }
+        if (name == "__schema" || name == "__type") && self.query.as_deref() == Some(parent) {
+            return meta(name);
+        }
+        self.types
+            .get(parent)?
+            .fields
+            .iter()
+            .find(|field| field.name == name)
+    }
+
+    /// The object types a value of the named type can be at runtime.
+    fn possible_types<'s>(&'s self, name: &'s str) -> HashSet<&'s str> {
+        match self.types.get(name) {
+            Some(info) if info.kind == Kind::Object => HashSet::from([name]),
+            Some(info) if info.kind == Kind::Union => {
+                info.members.iter().map(String::as_str).collect()
+            }
+            Some(info) if info.kind == Kind::Interface => self
+                .types
+                .iter()
+                .filter(|(_, t)| t.kind == Kind::Object && t.interfaces.iter().any(|i| i == name))
+                .map(|(n, _)| n.as_str())
+                .collect(),
+            _ => HashSet::new(),
+        }
+    }
+
+    /// Whether selections of both types return values of the same shape, as
+    /// required for fields that share a response key.
+    fn same_response_shape(&self, a: &Type<'static, String>, b: &Type<'static, String>) -> bool {
+        match (a, b) {
+            (Type::NonNullType(a), Type::NonNullType(b))
+            | (Type::ListType(a), Type::ListType(b)) => self.same_response_shape(a, b),
+            (Type::NamedType(a), Type::NamedType(b)) => {
+                a == b || !(self.is_leaf(a) || self.is_leaf(b))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A variable referenced in an operation or fragment, along with the type
+/// expected where it appears, if known.
+struct VariableUsage<'v> {
+    name: &'v str,
+    ty: Option<&'v Type<'static, String>>,
+    /// Whether the argument or input field the variable is passed to has a
+    /// default value.
+    has_default: bool,
+    position: Pos,
+}
+
+/// The variables and fragments an operation or fragment refers to directly.
+#[derive(Default)]
+struct References<'v> {
+    variables: Vec<VariableUsage<'v>>,
+    fragments: Vec<(&'v str, Pos)>,
+}
+
+/// A field collected for the field merging rule, along with the type it was
+/// selected on.
+struct CollectedField<'v> {
+    parent: &'v str,
+    field: &'v QueryField<'static, String>,
+    definition: Option<&'v Field<'static, String>>,
+}
+
+/// The parts shared by every kind of operation definition.
+struct Operation<'v> {
+    kind: &'static str,
+    location: DirectiveLocation,
+    position: Pos,
+    name: Option<&'v str>,
+    variables: &'v [VariableDefinition<'static, String>],
+    directives: &'v [Directive<'static, String>],
+    selection_set: &'v SelectionSet<'static, String>,
+}
+
+impl<'v> Operation<'v> {
+    fn new(op: &'v OperationDefinition<'static, String>) -> Self {
+        match op {
+            OperationDefinition::SelectionSet(selection_set) => Operation {
+                kind: "query",
+                location: DirectiveLocation::Query,
+                position: selection_set.span.0,
+                name: None,
+                variables: &[],
+                directives: &[],
+                selection_set,
+            },
+            OperationDefinition::Query(q) => Operation {
+                kind: "query",
+                location: DirectiveLocation::Query,
+                position: q.position,
+                name: q.name.as_deref(),
+                variables: &q.variable_definitions,
+                directives: &q.directives,
+                selection_set: &q.selection_set,
+            },
+            OperationDefinition::Mutation(m) => Operation {
+                kind: "mutation",
+                location: DirectiveLocation::Mutation,
+                position: m.position,
+                name: m.name.as_deref(),
+                variables: &m.variable_definitions,
+                directives: &m.directives,
+                selection_set: &m.selection_set,
+            },
+            OperationDefinition::Subscription(s) => Operation {
+                kind: "subscription",
+                location: DirectiveLocation::Subscription,
+                position: s.position,
+                name: s.name.as_deref(),
+                variables: &s.variable_definitions,
+                directives: &s.directives,
+                selection_set: &s.selection_set,
+            },
+        }
+    }
+}
+
+struct Validator<'v> {
+    schema: &'v SchemaIndex,
+    fragments: HashMap<&'v str, &'v FragmentDefinition<'static, String>>,
+    errors: Vec<ValidationError>,
+}
+
+impl<'v> Validator<'v> {
+    fn error(&mut self, message: String, locations: Vec<Pos>) {
+        self.errors.push(ValidationError { message, locations });
+    }
+
+    fn validate_document(&mut self, doc: &'v QueryDoc<'static, String>) {
+        let mut operations = Vec::new();
+        let mut fragments = Vec::new();
+        for def in &doc.definitions {
+            match def {
+                QueryDef::Operation(op) => operations.push(Operation::new(op)),
+                QueryDef::Fragment(fragment) => fragments.push(fragment),
+            }
+        }
+
+        let mut operation_names = HashSet::new();
+        for op in &operations {
+            match op.name {
+                Some(name) if !operation_names.insert(name) => self.error(
+                    format!("There can be only one operation named \"{name}\"."),
+                    vec![op.position],
+                ),
+                None if operations.len() > 1 => self.error(
+                    "This anonymous operation must be the only defined operation.".to_string(),
+                    vec![op.position],
+                ),
+                _ => (),
+            }
+        }
+
+        for fragment in &fragments {
+            if self.fragments.contains_key(fragment.name.as_str()) {
+                self.error(
+                    format!(
+                        "There can be only one fragment named \"{}\".",
+                        fragment.name
+                    ),
+                    vec![fragment.position],
+                );
+            } else {
+                self.fragments.insert(&fragment.name, fragment);
+            }
+        }
+
+        let mut fragment_references = HashMap::new();
+        for fragment in &fragments {
+            let references = self.validate_fragment(fragment);
+            fragment_references
+                .entry(fragment.name.as_str())
+                .or_insert(references);
+        }
+
+        let mut used_fragments = HashSet::new();
+        for op in &operations {
+            used_fragments.extend(self.validate_operation(op, &fragment_references));
+        }
+
+        for fragment in &fragments {
+            if !used_fragments.contains(fragment.name.as_str()) {
+                self.error(
+                    format!("Fragment \"{}\" is never used.", fragment.name),
+                    vec![fragment.position],
+                );
+            }
+        }
+
+        let mut visited = HashSet::new();
+        for fragment in &fragments {
+            self.check_fragment_cycles(
+                &fragment.name,
+                &fragment_references,
+                &mut visited,
+                &mut Vec::new(),
+            );
+        }
+    }
+
+    fn validate_fragment(
+        &mut self,
+        fragment: &'v FragmentDefinition<'static, String>,
+    ) -> References<'v> {
+        let mut references = References::default();
+        let TypeCondition::On(condition) = &fragment.type_condition;
+
+        self.check_directives(
+            &fragment.directives,
+            DirectiveLocation::FragmentDefinition,
+            &mut references,
+        );
+
+        if self.schema.kind(condition).is_none() {
+            self.error(
+                format!("Unknown type \"{condition}\"."),
+                vec![fragment.position],
+            );
+            collect_references(&fragment.selection_set, &mut references);
+        } else if !self.schema.is_composite(condition) {
+            self.error(
+                format!(
+                    "Fragment \"{}\" cannot condition on non composite type \"{condition}\".",
+                    fragment.name
+                ),
+                vec![fragment.position],
+            );
+            collect_references(&fragment.selection_set, &mut references);
+        } else {
+            self.check_selection_set(condition, &fragment.selection_set, &mut references);
+        }
+
+        references
+    }
+
+    /// Validates an operation and returns the names of the fragments it uses,
+    /// directly or through other fragments.
+    fn validate_operation(
+        &mut self,
+        op: &Operation<'v>,
+        fragment_references: &HashMap<&'v str, References<'v>>,
+    ) -> HashSet<&'v str> {
+        let schema = self.schema;
+        let mut references = References::default();
+
+        self.check_directives(op.directives, op.location.clone(), &mut references);
+
+        let mut definitions: HashMap<&str, &VariableDefinition<'static, String>> = HashMap::new();
+        for definition in op.variables {
+            if definitions.insert(&definition.name, definition).is_some() {
+                self.error(
+                    format!(
+                        "There can be only one variable named \"${}\".",
+                        definition.name
+                    ),
+                    vec![definition.position],
+                );
+            }
+
+            let type_name = util::named_type(&definition.var_type).unwrap();
+            match schema.kind(type_name) {
+                None => self.error(
+                    format!("Unknown type \"{type_name}\"."),
+                    vec![definition.position],
+                ),
+                Some(Kind::Scalar | Kind::Enum | Kind::InputObject) => {
+                    if let Some(default) = &definition.default_value {
+                        self.check_value(
+                            default,
+                            Some(&definition.var_type),
+                            false,
+                            definition.position,
+                            &mut References::default(),
+                        );
+                    }
+                }
+                Some(_) => self.error(
+                    format!(
+                        "Variable \"${}\" cannot be non-input type \"{}\".",
+                        definition.name, definition.var_type
+                    ),
+                    vec![definition.position],
+                ),
+            }
+        }
+
+        let root = match op.kind {
+            "mutation" => schema.mutation.as_deref(),
+            "subscription" => schema.subscription.as_deref(),
+            _ => schema.query.as_deref(),
+        };
+        match root {
+            Some(root) if schema.kind(root).is_some() => {
+                self.check_selection_set(root, op.selection_set, &mut references);
+                if op.kind == "subscription" {
+                    self.check_subscription_root(op, root);
+                }
+            }
+            _ => {
+                self.error(
+                    format!("Schema is not configured to execute {} operation.", op.kind),
+                    vec![op.position],
+                );
+                collect_references(op.selection_set, &mut references);
+            }
+        }
+
+        let mut usages: Vec<&VariableUsage> = references.variables.iter().collect();
+        let mut used_fragments = HashSet::new();
+        let mut stack: Vec<&str> = references.fragments.iter().map(|(name, _)| *name).collect();
+        while let Some(name) = stack.pop() {
+            if !used_fragments.insert(name) {
+                continue;
+            }
+            if let Some(fragment) = fragment_references.get(name) {
+                usages.extend(fragment.variables.iter());
+                stack.extend(fragment.fragments.iter().map(|(name, _)| *name));
+            }
+        }
+
+        for usage in &usages {
+            match definitions.get(usage.name) {
+                None => {
+                    let message = match op.name {
+                        Some(op_name) => format!(
+                            "Variable \"${}\" is not defined by operation \"{op_name}\".",
+                            usage.name
+                        ),
+                        None => format!("Variable \"${}\" is not defined.", usage.name),
+                    };
+                    self.error(message, vec![usage.position, op.position]);
+                }
+                Some(definition) if !is_variable_usage_allowed(definition, usage) => {
+                    self.error(
+                        format!(
+                            "Variable \"${}\" of type \"{}\" used in position expecting type \"{}\".",
+                            usage.name,
+                            definition.var_type,
+                            usage.ty.unwrap()
+                        ),
+                        vec![definition.position, usage.position],
+                    );
+                }
+                Some(_) => (),
+            }
+        }
+
+        for definition in op.variables {
+            if !usages.iter().any(|usage| usage.name == definition.name) {
+                let message = match op.name {
+                    Some(op_name) => format!(
+                        "Variable \"${}\" is never used in operation \"{op_name}\".",
+                        definition.name
+                    ),
+                    None => format!("Variable \"${}\" is never used.", definition.name),
+                };
+                self.error(message, vec![definition.position]);
+            }
+        }
+
+        used_fragments
+    }
+
+    fn check_subscription_root(&mut self, op: &Operation<'v>, root: &'v str) {
+        let mut fields = Vec::new();
+        self.collect_fields(root, op.selection_set, &mut fields, &mut HashSet::new());
+
+        let subject = match op.name {
+            Some(name) => format!("Subscription \"{name}\""),
+            None => "Anonymous Subscription".to_string(),
+        };
+        let keys: HashSet<&str> = fields.iter().map(|(key, _)| *key).collect();
+        if keys.len() > 1 {
+            self.error(
+                format!("{subject} must select only one top level field."),
+                vec![op.position],
+            );
+        }
+        if fields.iter().any(|(_, f)| f.field.name.starts_with("__")) {
+            self.error(
+                format!("{subject} must not select an introspection top level field."),
+                vec![op.position],
+            );
+        }
+    }
+
+    fn check_selection_set(
+        &mut self,
+        parent: &'v str,
+        selection_set: &'v SelectionSet<'static, String>,
+        references: &mut References<'v>,
+    ) {
+        let schema = self.schema;
+
+        for selection in &selection_set.items {
+            match selection {
+                Selection::Field(field) => self.check_field(parent, field, references),
+                Selection::FragmentSpread(spread) => {
+                    self.check_directives(
+                        &spread.directives,
+                        DirectiveLocation::FragmentSpread,
+                        references,
+                    );
+                    references
+                        .fragments
+                        .push((&spread.fragment_name, spread.position));
+
+                    match self.fragments.get(spread.fragment_name.as_str()) {
+                        None => self.error(
+                            format!("Unknown fragment \"{}\".", spread.fragment_name),
+                            vec![spread.position],
+                        ),
+                        Some(fragment) => {
+                            let TypeCondition::On(condition) = &fragment.type_condition;
+                            self.check_spread_possible(
+                                parent,
+                                condition,
+                                Some(&spread.fragment_name),
+                                spread.position,
+                            );
+                        }
+                    }
+                }
+                Selection::InlineFragment(inline) => {
+                    self.check_directives(
+                        &inline.directives,
+                        DirectiveLocation::InlineFragment,
+                        references,
+                    );
+
+                    let type_name = match &inline.type_condition {
+                        None => parent,
+                        Some(TypeCondition::On(condition)) => {
+                            if schema.kind(condition).is_none() {
+                                self.error(
+                                    format!("Unknown type \"{condition}\"."),
+                                    vec![inline.position],
+                                );
+                                collect_references(&inline.selection_set, references);
+                                continue;
+                            }
+                            if !schema.is_composite(condition) {
+                                self.error(
+                                    format!(
+                                        "Fragment cannot condition on non composite type \"{condition}\"."
+                                    ),
+                                    vec![inline.position],
+                                );
+                                collect_references(&inline.selection_set, references);
+                                continue;
+                            }
+                            self.check_spread_possible(parent, condition, None, inline.position);
+                            condition
+                        }
+                    };
+                    self.check_selection_set(type_name, &inline.selection_set, references);
+                }
+            }
+        }
+
+        self.check_merging(parent, selection_set);
+    }
+
+    fn check_field(
+        &mut self,
+        parent: &'v str,
+        field: &'v QueryField<'static, String>,
+        references: &mut References<'v>,
+    ) {
+        let schema = self.schema;
+
+        self.check_directives(&field.directives, DirectiveLocation::Field, references);
+
+        let Some(definition) = schema.field(parent, &field.name) else {
+            self.error(
+                format!(
+                    "Cannot query field \"{}\" on type \"{parent}\".",
+                    field.name
+                ),
+                vec![field.position],
+            );
+            for (_, value) in &field.arguments {
+                collect_value_references(value, field.position, references);
+            }
+            collect_references(&field.selection_set, references);
+            return;
+        };
+
+        self.check_arguments(
+            &field.arguments,
+            &definition.arguments,
+            "Field",
+            &format!("{parent}.{}", field.name),
+            field.position,
+            references,
+        );
+
+        let type_name = util::named_type(&definition.field_type).unwrap();
+        let has_selection = !field.selection_set.items.is_empty();
+        if schema.is_leaf(type_name) {
+            if has_selection {
+                self.error(
+                    format!(
+                        "Field \"{}\" must not have a selection since type \"{}\" has no subfields.",
+                        field.name, definition.field_type
+                    ),
+                    vec![field.position],
+                );
+            }
+        } else if schema.is_composite(type_name) {
+            if has_selection {
+                self.check_selection_set(type_name, &field.selection_set, references);
+            } else {
+                self.error(
+                    format!(
+                        "Field \"{0}\" of type \"{1}\" must have a selection of subfields. Did you mean \"{0} {{ ... }}\"?",
+                        field.name, definition.field_type
+                    ),
+                    vec![field.position],
+                );
+            }
+        } else {
+            collect_references(&field.selection_set, references);
+        }
+    }
+
+    fn check_arguments(
+        &mut self,
+        arguments: &'v [(String, Value<'static, String>)],
+        definitions: &'v [InputValue<'static, String>],
+        owner_kind: &str,
+        owner: &str,
+        position: Pos,
+        references: &mut References<'v>,
+    ) {
+        let mut seen = HashSet::new();
+        for (name, value) in arguments {
+            if !seen.insert(name) {
+                self.error(
+                    format!("There can be only one argument named \"{name}\"."),
+                    vec![position],
+                );
+            }
+
+            match definitions.iter().find(|d| d.name == *name) {
+                Some(definition) => self.check_value(
+                    value,
+                    Some(&definition.value_type),
+                    definition.default_value.is_some(),
+                    position,
+                    references,
+                ),
+                None => {
+                    self.error(
+                        format!(
+                            "Unknown argument \"{name}\" on {} \"{owner}\".",
+                            owner_kind.to_lowercase()
+                        ),
+                        vec![position],
+                    );
+                    collect_value_references(value, position, references);
+                }
+            }
+        }
+
+        for definition in definitions {
+            let required = matches!(definition.value_type, Type::NonNullType(_))
+                && definition.default_value.is_none();
+            if required && !arguments.iter().any(|(name, _)| *name == definition.name) {
+                self.error(
+                    format!(
+                        "{owner_kind} \"{owner}\" argument \"{}\" of type \"{}\" is required, but it was not provided.",
+                        definition.name, definition.value_type
+                    ),
+                    vec![position],
+                );
+            }
+        }
+    }
+
+    fn check_directives(
+        &mut self,
+        directives: &'v [Directive<'static, String>],
+        location: DirectiveLocation,
+        references: &mut References<'v>,
+    ) {
+        let schema = self.schema;
+        let mut seen = HashSet::new();
+
+        for directive in directives {
+            let Some(definition) = schema.directives.get(&directive.name) else {
+                self.error(
+                    format!("Unknown directive \"@{}\".", directive.name),
+                    vec![directive.position],
+                );
+                for (_, value) in &directive.arguments {
+                    collect_value_references(value, directive.position, references);
+                }
+                continue;
+            };
+
+            if !definition.locations.contains(&location) {
+                self.error(
+                    format!(
+                        "Directive \"@{}\" may not be used on {}.",
+                        directive.name,
+                        location.as_str()
+                    ),
+                    vec![directive.position],
+                );
+            }
+            if !definition.repeatable && !seen.insert(directive.name.as_str()) {
+                self.error(
+                    format!(
+                        "The directive \"@{}\" can only be used once at this location.",
+                        directive.name
+                    ),
+                    vec![directive.position],
+                );
+            }
+
+            self.check_arguments(
+                &directive.arguments,
+                &definition.arguments,
+                "Directive",
+                &format!("@{}", directive.name),
+                directive.position,
+                references,
+            );
+        }
+    }
+
+    /// Checks that a literal can be coerced to the expected type, recording
+    /// the variables it contains.
+    fn check_value(
+        &mut self,
+        value: &'v Value<'static, String>,
+        ty: Option<&'v Type<'static, String>>,
+        has_default: bool,
+        position: Pos,
+        references: &mut References<'v>,
+    ) {
+        if let Value::Variable(name) = value {
+            references.variables.push(VariableUsage {
+                name,
+                ty,
+                has_default,
+                position,
+            });
+            return;
+        }
+        let Some(ty) = ty else {
+            collect_value_references(value, position, references);
+            return;
+        };
+
+        match (ty, value) {
+            (Type::NonNullType(_), Value::Null) => self.error(
+                format!("Expected value of type \"{ty}\", found null."),
+                vec![position],
+            ),
+            (Type::NonNullType(inner), _) => {
+                self.check_value(value, Some(inner), false, position, references)
+            }
+            (_, Value::Null) => (),
+            (Type::ListType(inner), Value::List(items)) => {
+                for item in items {
+                    self.check_value(item, Some(inner), false, position, references);
+                }
+            }
+            (Type::ListType(inner), _) => {
+                self.check_value(value, Some(inner), false, position, references)
+            }
+            (Type::NamedType(name), _) => self.check_named_value(name, value, position, references),
+        }
+    }
+
+    fn check_named_value(
+        &mut self,
+        type_name: &'v str,
+        value: &'v Value<'static, String>,
+        position: Pos,
+        references: &mut References<'v>,
+    ) {
+        let Some(info) = self.schema.types.get(type_name) else {
+            collect_value_references(value, position, references);
+            return;
+        };
+
+        let valid = match info.kind {
+            Kind::Scalar => match type_name {
+                "Int" => matches!(
+                    value,
+                    Value::Int(n) if n.as_i64().is_some_and(|n| i32::try_from(n).is_ok())
+                ),
+                "Float" => matches!(value, Value::Int(_) | Value::Float(_)),
+                "String" => matches!(value, Value::String(_)),
+                "Boolean" => matches!(value, Value::Boolean(_)),
+                "ID" => matches!(value, Value::String(_) | Value::Int(_)),
+                _ => {
+                    collect_value_references(value, position, references);
+                    true
+                }
+            },
+            Kind::Enum => matches!(value, Value::Enum(v) if info.values.contains(v)),
+            Kind::InputObject => match value {
+                Value::Object(fields) => {
+                    for (name, field_value) in fields {
+                        match info.input_fields.iter().find(|f| f.name == *name) {
+                            Some(definition) => self.check_value(
+                                field_value,
+                                Some(&definition.value_type),
+                                definition.default_value.is_some(),
+                                position,
+                                references,
+                            ),
+                            None => {
+                                self.error(
+                                    format!(
+                                        "Field \"{name}\" is not defined by type \"{type_name}\"."
+                                    ),
+                                    vec![position],
+                                );
+                                collect_value_references(field_value, position, references);
+                            }
+                        }
+                    }
+                    for definition in &info.input_fields {
+                        let required = matches!(definition.value_type, Type::NonNullType(_))
+                            && definition.default_value.is_none();
+                        if required && !fields.contains_key(&definition.name) {
+                            self.error(
+                                format!(
+                                    "Field \"{type_name}.{}\" of required type \"{}\" was not provided.",
+                                    definition.name, definition.value_type
+                                ),
+                                vec![position],
+                            );
+                        }
+                    }
+                    true
+                }
+                _ => false,
+            },
+            Kind::Object | Kind::Interface | Kind::Union => true,
+        };
+
+        if !valid {
+            self.error(
+                format!("Expected value of type \"{type_name}\", found {value}."),
+                vec![position],
+            );
+        }
+    }
+
+    fn check_spread_possible(
+        &mut self,
+        parent: &str,
+        condition: &str,
+        fragment: Option<&str>,
+        position: Pos,
+    ) {
+        let schema = self.schema;
+        if !schema.is_composite(parent) || !schema.is_composite(condition) {
+            return;
+        }
+        if schema
+            .possible_types(parent)
+            .is_disjoint(&schema.possible_types(condition))
+        {
+            let subject = match fragment {
+                Some(name) => format!("Fragment \"{name}\""),
+                None => "Fragment".to_string(),
+            };
+            self.error(
+                format!(
+                    "{subject} cannot be spread here as objects of type \"{parent}\" can never be of type \"{condition}\"."
+                ),
+                vec![position],
+            );
+        }
+    }
+
+    fn check_fragment_cycles(
+        &mut self,
+        name: &'v str,
+        fragment_references: &HashMap<&'v str, References<'v>>,
+        visited: &mut HashSet<&'v str>,
+        path: &mut Vec<&'v str>,
+    ) {
+        if !visited.insert(name) {
+            return;
+        }
+        let Some(references) = fragment_references.get(name) else {
+            return;
+        };
+
+        path.push(name);
+        for (spread, position) in &references.fragments {
+            match path.iter().position(|p| p == spread) {
+                Some(start) => {
+                    let via = &path[start + 1..];
+                    let message = if via.is_empty() {
+                        format!("Cannot spread fragment \"{spread}\" within itself.")
+                    } else {
+                        format!(
+                            "Cannot spread fragment \"{spread}\" within itself via \"{}\".",
+                            via.join("\", \"")
+                        )
+                    };
+                    self.error(message, vec![*position]);
+                }
+                None => self.check_fragment_cycles(spread, fragment_references, visited, path),
+            }
+        }
+        path.pop();
+    }
+
+    /// Checks that fields sharing a response key in the selection set, after
+    /// expanding fragments, can be merged into a single result.
+    fn check_merging(&mut self, parent: &'v str, selection_set: &'v SelectionSet<'static, String>) {
+        let mut fields = Vec::new();
+        self.collect_fields(parent, selection_set, &mut fields, &mut HashSet::new());
+
+        for (key, group) in group_by_response_key(fields) {
+            for (i, a) in group.iter().enumerate() {
+                for b in &group[i + 1..] {
+                    if let Some(reason) = self.conflict(a, b, false) {
+                        self.error(
+                            format!(
+                                "Fields \"{key}\" conflict because {reason}. Use different aliases on the fields to fetch both if this was intentional."
+                            ),
+                            vec![a.field.position, b.field.position],
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Explains why two fields with the same response key cannot be merged.
+    /// Fields on distinct object types are never selected together, so only
+    /// the shapes of their results have to agree.
+    fn conflict(
+        &self,
+        a: &CollectedField<'v>,
+        b: &CollectedField<'v>,
+        exclusive: bool,
+    ) -> Option<String> {
+        let schema = self.schema;
+        let exclusive = exclusive
+            || (a.parent != b.parent
+                && schema.kind(a.parent) == Some(Kind::Object)
+                && schema.kind(b.parent) == Some(Kind::Object));
+
+        if !exclusive {
+            if a.field.name != b.field.name {
+                return Some(format!(
+                    "\"{}\" and \"{}\" are different fields",
+                    a.field.name, b.field.name
+                ));
+            }
+            if !same_arguments(&a.field.arguments, &b.field.arguments) {
+                return Some("they have differing arguments".to_string());
+            }
+        }
+
+        let (Some(a_def), Some(b_def)) = (a.definition, b.definition) else {
+            return None;
+        };
+        if !schema.same_response_shape(&a_def.field_type, &b_def.field_type) {
+            return Some(format!(
+                "they return conflicting types \"{}\" and \"{}\"",
+                a_def.field_type, b_def.field_type
+            ));
+        }
+
+        let a_type = util::named_type(&a_def.field_type).unwrap();
+        let b_type = util::named_type(&b_def.field_type).unwrap();
+        if !schema.is_composite(a_type) || !schema.is_composite(b_type) {
+            return None;
+        }
+
+        let mut subfields = Vec::new();
+        let mut visited = HashSet::new();
+        self.collect_fields(a_type, &a.field.selection_set, &mut subfields, &mut visited);
+        self.collect_fields(b_type, &b.field.selection_set, &mut subfields, &mut visited);
+        for (key, group) in group_by_response_key(subfields) {
+            for (i, x) in group.iter().enumerate() {
+                for y in &group[i + 1..] {
+                    if let Some(reason) = self.conflict(x, y, exclusive) {
+                        return Some(format!("subfields \"{key}\" conflict because {reason}"));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Collects the fields of a selection set by response key, expanding
+    /// fragment spreads and inline fragments.
+    fn collect_fields(
+        &self,
+        parent: &'v str,
+        selection_set: &'v SelectionSet<'static, String>,
+        fields: &mut Vec<(&'v str, CollectedField<'v>)>,
+        visited: &mut HashSet<&'v str>,
+    ) {
+        let schema = self.schema;
+
+        for selection in &selection_set.items {
+            match selection {
+                Selection::Field(field) => {
+                    let key = field.alias.as_deref().unwrap_or(&field.name);
+                    fields.push((
+                        key,
+                        CollectedField {
+                            parent,
+                            field,
+                            definition: schema.field(parent, &field.name),
+                        },
+                    ));
+                }
+                Selection::FragmentSpread(spread) => {
+                    if !visited.insert(&spread.fragment_name) {
+                        continue;
+                    }
+                    if let Some(fragment) = self.fragments.get(spread.fragment_name.as_str()) {
+                        let TypeCondition::On(condition) = &fragment.type_condition;
+                        if schema.kind(condition).is_some() {
+                            self.collect_fields(
+                                condition,
+                                &fragment.selection_set,
+                                fields,
+                                visited,
+                            );
+                        }
+                    }
+                }
+                Selection::InlineFragment(inline) => {
+                    let type_name = match &inline.type_condition {
+                        Some(TypeCondition::On(condition)) => condition.as_str(),
+                        None => parent,
+                    };
+                    if schema.kind(type_name).is_some() {
+                        self.collect_fields(type_name, &inline.selection_set, fields, visited);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn group_by_response_key<'v>(
+    fields: Vec<(&'v str, CollectedField<'v>)>,
+) -> Vec<(&'v str, Vec<CollectedField<'v>>)> {
+    let mut groups: Vec<(&str, Vec<CollectedField>)> = Vec::new();
+    for (key, field) in fields {
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, group)) => group.push(field),
+            None => groups.push((key, vec![field])),
+        }
+    }
+    groups
+}
+
+fn same_arguments(
+    a: &[(String, Value<'static, String>)],
+    b: &[(String, Value<'static, String>)],
+) -> bool {
+    a.len() == b.len() && a.iter().all(|arg| b.contains(arg))
+}
+
+/// Whether a variable of the defined type may be passed where the usage
+/// expects its type, allowing a nullable variable in a non-null position when
+/// either side provides a default.
+fn is_variable_usage_allowed(
+    definition: &VariableDefinition<'static, String>,
+    usage: &VariableUsage,
+) -> bool {
+    let Some(location) = usage.ty else {
+        return true;
+    };
+
+    if let Type::NonNullType(inner) = location {
+        if !matches!(definition.var_type, Type::NonNullType(_)) {
+            let has_non_null_default = definition
+                .default_value
+                .as_ref()
+                .is_some_and(|value| *value != Value::Null);
+            if !has_non_null_default && !usage.has_default {
+                return false;
+            }
+            return are_types_compatible(&definition.var_type, inner);
+        }
+    }
+    are_types_compatible(&definition.var_type, location)
+}
+
+fn are_types_compatible(
+    variable: &Type<'static, String>,
+    location: &Type<'static, String>,
+) -> bool {
+    match (variable, location) {
+        (Type::NonNullType(variable), Type::NonNullType(location)) => {
+            are_types_compatible(variable, location)
+        }
+        (_, Type::NonNullType(_)) => false,
+        (Type::NonNullType(variable), _) => are_types_compatible(variable, location),
+        (Type::ListType(variable), Type::ListType(location)) => {
+            are_types_compatible(variable, location)
+        }
+        (Type::NamedType(variable), Type::NamedType(location)) => variable == location,
+        _ => false,
+    }
+}
+
+/// Records the variables and fragments used below a selection set whose type
+/// is unknown, so they are reported neither as unused nor as undefined.
+fn collect_references<'v>(
+    selection_set: &'v SelectionSet<'static, String>,
+    references: &mut References<'v>,
+) {
+    for selection in &selection_set.items {
+        match selection {
+            Selection::Field(field) => {
+                for (_, value) in &field.arguments {
+                    collect_value_references(value, field.position, references);
+                }
+                collect_directive_references(&field.directives, references);
+                collect_references(&field.selection_set, references);
+            }
+            Selection::FragmentSpread(spread) => {
+                references
+                    .fragments
+                    .push((&spread.fragment_name, spread.position));
+                collect_directive_references(&spread.directives, references);
+            }
+            Selection::InlineFragment(inline) => {
+                collect_directive_references(&inline.directives, references);
+                collect_references(&inline.selection_set, references);
+            }
+        }
+    }
+}
+
+fn collect_directive_references<'v>(
+    directives: &'v [Directive<'static, String>],
+    references: &mut References<'v>,
+) {
+    for directive in directives {
+        for (_, value) in &directive.arguments {
+            collect_value_references(value, directive.position, references);
+        }
+    }
+}
+
+fn collect_value_references<'v>(
+    value: &'v Value<'static, String>,
+    position: Pos,
+    references: &mut References<'v>,
+) {
+    match value {
+        Value::Variable(name) => references.variables.push(VariableUsage {
+            name,
+            ty: None,
+            has_default: false,
+            position,
+        }),
+        Value::List(items) => {
+            for item in items {
+                collect_value_references(item, position, references);
+            }
+        }
+        Value::Object(fields) => {
+            for field_value in fields.values() {
+                collect_value_references(field_value, position, references);
+            }
+        }
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate;
+    use graphql_parser::{parse_query, parse_schema};
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    const SCHEMA: &str = indoc! {"
+        type Query {
+          user(id: ID!): User
+          users(filter: UserFilter, first: Int = 10): [User!]!
+          search(term: String!): [SearchResult!]!
+          node(id: ID!): Node
+        }
+
+        type Mutation {
+          rename(id: ID!, name: String!): User
+        }
+
+        interface Node {
+          id: ID!
+        }
+
+        type User implements Node {
+          id: ID!
+          name: String
+          age: Int
+          role: Role!
+          friends(first: Int): [User!]!
+        }
+
+        type Post implements Node {
+          id: ID!
+          title: String!
+        }
+
+        union SearchResult = User | Post
+
+        enum Role {
+          ADMIN
+          GUEST
+        }
+
+        input UserFilter {
+          role: Role!
+          nameContains: String
+        }
+    "};
+
+    fn process(query: &str) -> Vec<String> {
+        let schema = parse_schema::<String>(SCHEMA).unwrap();
+        let query = parse_query::<String>(query).unwrap();
+
+        validate(&schema, &query)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn valid_query() {
+        let query = indoc! {"
+            query Users($role: Role!, $first: Int) {
+              users(filter: {role: $role}, first: $first) {
+                ...UserFields
+                friends(first: 3) @include(if: true) {
+                  __typename
+                  id
+                }
+              }
+              search(term: \"rust\") {
+                ... on Post {
+                  title
+                }
+                ... on Node {
+                  id
+                }
+              }
+            }
+
+            fragment UserFields on User {
+              id
+              name
+              role
+            }
+        "};
+
+        assert_eq!(process(query), Vec::<String>::new());
+    }
+
+    #[test]
+    fn fields_and_arguments() {
+        let query = indoc! {"
+            {
+              user {
+                email
+                role {
+                  name
+                }
+              }
+              users(filter: {nameContains: 3}, limit: 1) {
+                friends
+              }
+              search(term: \"a\") {
+                id
+              }
+            }
+        "};
+
+        assert_eq!(
+            process(query),
+            vec![
+                "2:3: Field \"Query.user\" argument \"id\" of type \"ID!\" is required, but it was not provided.".to_string(),
+                "3:5: Cannot query field \"email\" on type \"User\".".to_string(),
+                "4:5: Field \"role\" must not have a selection since type \"Role!\" has no subfields.".to_string(),
+                "8:3: Expected value of type \"String\", found 3.".to_string(),
+                "8:3: Field \"UserFilter.role\" of required type \"Role!\" was not provided.".to_string(),
+                "8:3: Unknown argument \"limit\" on field \"Query.users\".".to_string(),
+                "9:5: Field \"friends\" of type \"[User!]!\" must have a selection of subfields. Did you mean \"friends { ... }\"?".to_string(),
+                "12:5: Cannot query field \"id\" on type \"SearchResult\".".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn variables() {
+        let query = indoc! {"
+            query Users($first: Int, $unused: String, $role: String, $user: User) {
+              users(first: $first, filter: {role: $role}) {
+                friends(first: $missing) {
+                  id
+                }
+              }
+              user(id: $first) {
+                id
+              }
+            }
+        "};
+
+        assert_eq!(
+            process(query),
+            vec![
+                "1:13: Variable \"$first\" of type \"Int\" used in position expecting type \"ID!\".".to_string(),
+                "1:26: Variable \"$unused\" is never used in operation \"Users\".".to_string(),
+                "1:43: Variable \"$role\" of type \"String\" used in position expecting type \"Role!\".".to_string(),
+                "1:58: Variable \"$user\" cannot be non-input type \"User\".".to_string(),
+                "1:58: Variable \"$user\" is never used in operation \"Users\".".to_string(),
+                "3:5: Variable \"$missing\" is not defined by operation \"Users\".".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn fragments() {
+        let query = indoc! {"
+            {
+              node(id: 1) {
+                ...A
+                ...Missing
+                ... on Role {
+                  id
+                }
+              }
+              user(id: 1) {
+                ... on Post {
+                  title
+                }
+              }
+            }
+
+            fragment A on User {
+              ...B
+            }
+
+            fragment B on User {
+              ...A
+            }
+
+            fragment Unused on Post {
+              id
+            }
+        "};
+
+        assert_eq!(
+            process(query),
+            vec![
+                "4:8: Unknown fragment \"Missing\".".to_string(),
+                "5:9: Fragment cannot condition on non composite type \"Role\".".to_string(),
+                "10:9: Fragment cannot be spread here as objects of type \"User\" can never be of type \"Post\".".to_string(),
+                "21:6: Cannot spread fragment \"A\" within itself via \"B\".".to_string(),
+                "24:1: Fragment \"Unused\" is never used.".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn conflicting_fields() {
+        let query = indoc! {"
+            {
+              user(id: 1) {
+                name: age
+                name
+                friends(first: 1) {
+                  id
+                }
+                friends(first: 2) {
+                  id
+                }
+              }
+              search(term: \"a\") {
+                ... on User {
+                  value: age
+                }
+                ... on Post {
+                  value: title
+                }
+              }
+            }
+        "};
+
+        assert_eq!(
+            process(query),
+            vec![
+                "3:5: Fields \"name\" conflict because \"age\" and \"name\" are different fields. Use different aliases on the fields to fetch both if this was intentional.".to_string(),
+                "5:5: Fields \"friends\" conflict because they have differing arguments. Use different aliases on the fields to fetch both if this was intentional.".to_string(),
+                "14:7: Fields \"value\" conflict because they return conflicting types \"Int\" and \"String!\". Use different aliases on the fields to fetch both if this was intentional.".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn operations_and_directives() {
+        let query = indoc! {"
+            query A @skip(if: true) {
+              user(id: 1) @include(if: true) @include(if: false) @unknown {
+                id
+              }
+            }
+
+            query A {
+              __typename
+            }
+
+            subscription {
+              __typename
+            }
+
+            {
+              __schema {
+                queryType {
+                  name
+                }
+              }
+            }
+        "};
+
+        assert_eq!(
+            process(query),
+            vec![
+                "1:9: Directive \"@skip\" may not be used on QUERY.".to_string(),
+                "2:34: The directive \"@include\" can only be used once at this location."
+                    .to_string(),
+                "2:54: Unknown directive \"@unknown\".".to_string(),
+                "7:1: There can be only one operation named \"A\".".to_string(),
+                "11:1: This anonymous operation must be the only defined operation.".to_string(),
+                "11:1: Schema is not configured to execute subscription operation.".to_string(),
+                "15:1: This anonymous operation must be the only defined operation.".to_string(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,349 @@
+/// Tags whose template literals hold GraphQL documents, used either as
+/// ``gql`...` `` or as ``graphql(`...`)``.
+const TAGS: &[&str] = &["gql", "graphql"];
+
+/// A GraphQL document embedded in a tagged template literal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    /// One-based line and column in the source file where the template's
+    /// content starts.
+    pub line: usize,
+    pub column: usize,
+    /// The content of the template. Interpolations are replaced by spaces so
+    /// that positions within the text still match the source file.
+    pub text: String,
+    /// The variable the template is assigned to, as in
+    /// ``const UserFields = gql`...` ``.
+    pub name: Option<String>,
+    /// The identifiers interpolated into the template with `${...}`, usually
+    /// other templates holding the fragments it spreads.
+    pub interpolations: Vec<String>,
+}
+
+/// A named import, `import { imported as local } from "module"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+    pub local: String,
+    pub imported: String,
+    pub module: String,
+}
+
+/// Finds the GraphQL templates in a TypeScript or JavaScript source file.
+/// Strings and comments are skipped so that tags mentioned inside them are
+/// not picked up.
+pub fn extract_templates(source: &str) -> Vec<Template> {
+    let chars: Vec<char> = source.chars().collect();
+    let positions = positions(&chars);
+    let mut templates = Vec::new();
+
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 1;
+            }
+            quote @ ('\'' | '"') => {
+                i += 1;
+                while i < chars.len() && chars[i] != quote && chars[i] != '\n' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            '`' => {
+                let tag = tag_start(&chars, i);
+                let (end, text, interpolations) = read_template(&chars, i + 1);
+                if let Some(tag) = tag {
+                    let (line, column) = positions[i + 1];
+                    templates.push(Template {
+                        line,
+                        column,
+                        text,
+                        name: assignment_name(&chars, tag),
+                        interpolations,
+                    });
+                }
+                i = end;
+            }
+            _ => (),
+        }
+        i += 1;
+    }
+
+    templates
+}
+
+/// Finds the named imports of a source file. Default and namespace imports
+/// are ignored since they cannot be matched to a template by name.
+pub fn extract_imports(source: &str) -> Vec<Import> {
+    let mut imports = Vec::new();
+    let mut rest = source;
+
+    while let Some(index) = rest.find("import") {
+        let boundary = rest[..index]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !is_identifier_char(c));
+        rest = &rest[index + "import".len()..];
+        if !boundary {
+            continue;
+        }
+
+        let clause = rest.trim_start();
+        let clause = clause.strip_prefix("type").unwrap_or(clause).trim_start();
+        let Some(clause) = clause.strip_prefix('{') else {
+            continue;
+        };
+        let Some((names, after)) = clause.split_once('}') else {
+            continue;
+        };
+        let Some(after) = after.trim_start().strip_prefix("from") else {
+            continue;
+        };
+        let after = after.trim_start();
+        let Some(quote) = after.chars().next().filter(|c| *c == '\'' || *c == '"') else {
+            continue;
+        };
+        let Some((module, _)) = after[1..].split_once(quote) else {
+            continue;
+        };
+
+        for name in names.split(',') {
+            let name = name.trim();
+            let name = name.strip_prefix("type ").unwrap_or(name);
+            let (imported, local) = match name.split_once(" as ") {
+                Some((imported, local)) => (imported.trim(), local.trim()),
+                None => (name, name),
+            };
+            if !imported.is_empty() {
+                imports.push(Import {
+                    local: local.to_string(),
+                    imported: imported.to_string(),
+                    module: module.to_string(),
+                });
+            }
+        }
+    }
+
+    imports
+}
+
+fn positions(chars: &[char]) -> Vec<(usize, usize)> {
+    let mut positions = Vec::with_capacity(chars.len() + 1);
+    let (mut line, mut column) = (1, 1);
+    for c in chars {
+        positions.push((line, column));
+        if *c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+    positions.push((line, column));
+    positions
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+fn skip_whitespace_back(chars: &[char], mut end: usize) -> usize {
+    while end > 0 && chars[end - 1].is_whitespace() {
+        end -= 1;
+    }
+    end
+}
+
+/// Reads the identifier ending right before `end`, returning its start.
+fn identifier_before(chars: &[char], end: usize) -> Option<usize> {
+    let mut start = end;
+    while start > 0 && is_identifier_char(chars[start - 1]) {
+        start -= 1;
+    }
+    (start < end).then_some(start)
+}
+
+/// If the backtick at `backtick` opens a GraphQL template, returns the index
+/// where its tag starts.
+fn tag_start(chars: &[char], backtick: usize) -> Option<usize> {
+    let mut end = skip_whitespace_back(chars, backtick);
+    if end > 0 && chars[end - 1] == '(' {
+        end = skip_whitespace_back(chars, end - 1);
+    }
+    let start = identifier_before(chars, end)?;
+    let tag: String = chars[start..end].iter().collect();
+    let member_access = start > 0 && chars[start - 1] == '.';
+    (TAGS.contains(&tag.as_str()) && !member_access).then_some(start)
+}
+
+/// Reads a template literal starting after its opening backtick, returning
+/// the index of the closing backtick, the text with interpolations blanked
+/// out and the interpolated identifiers.
+fn read_template(chars: &[char], start: usize) -> (usize, String, Vec<String>) {
+    let mut text = String::new();
+    let mut interpolations = Vec::new();
+
+    let mut i = start;
+    while i < chars.len() {
+        match chars[i] {
+            '`' => break,
+            '\\' => {
+                text.extend(&chars[i..(i + 2).min(chars.len())]);
+                i += 2;
+            }
+            '$' if chars.get(i + 1) == Some(&'{') => {
+                let mut depth = 1;
+                let mut end = i + 2;
+                while end < chars.len() && depth > 0 {
+                    match chars[end] {
+                        '{' => depth += 1,
+                        '}' => depth -= 1,
+                        _ => (),
+                    }
+                    end += 1;
+                }
+
+                let expression: String = chars[i + 2..end.saturating_sub(1)].iter().collect();
+                let expression = expression.trim();
+                if !expression.is_empty()
+                    && expression
+                        .chars()
+                        .all(|c| is_identifier_char(c) || c == '.')
+                {
+                    interpolations.push(expression.to_string());
+                }
+
+                text.extend(
+                    chars[i..end]
+                        .iter()
+                        .map(|c| if *c == '\n' { '\n' } else { ' ' }),
+                );
+                i = end;
+            }
+            c => {
+                text.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    (i, text, interpolations)
+}
+
+/// Finds the variable a template is assigned to, skipping a type annotation
+/// as in `const Query: DocumentNode = gql`.
+fn assignment_name(chars: &[char], tag: usize) -> Option<String> {
+    let end = skip_whitespace_back(chars, tag);
+    if end == 0 || chars[end - 1] != '=' {
+        return None;
+    }
+    if end > 1 && matches!(chars[end - 2], '=' | '!' | '<' | '>') {
+        return None;
+    }
+
+    let end = skip_whitespace_back(chars, end - 1);
+    let start = identifier_before(chars, end)?;
+    let before = skip_whitespace_back(chars, start);
+    if before > 0 && chars[before - 1] == ':' {
+        let end = skip_whitespace_back(chars, before - 1);
+        let start = identifier_before(chars, end)?;
+        return Some(chars[start..end].iter().collect());
+    }
+    Some(chars[start..end].iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_imports, extract_templates, Import, Template};
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn extracts_tagged_templates() {
+        let source = indoc! {r#"
+            import { gql } from "@apollo/client";
+
+            // gql`not a template`
+            const label = "gql`not a template either`";
+
+            export const UserFields = gql`
+              fragment UserFields on User {
+                id
+              }
+            `;
+
+            export const GetUser: DocumentNode = graphql(`
+              query GetUser {
+                user {
+                  ...UserFields
+                }
+              }
+              ${UserFields}
+            `);
+
+            const other = html`<p>${label}</p>`;
+        "#};
+
+        assert_eq!(
+            extract_templates(source),
+            vec![
+                Template {
+                    line: 6,
+                    column: 31,
+                    text: "\n  fragment UserFields on User {\n    id\n  }\n".to_string(),
+                    name: Some("UserFields".to_string()),
+                    interpolations: vec![],
+                },
+                Template {
+                    line: 12,
+                    column: 47,
+                    text: "\n  query GetUser {\n    user {\n      ...UserFields\n    }\n  }\n               \n"
+                        .to_string(),
+                    name: Some("GetUser".to_string()),
+                    interpolations: vec!["UserFields".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn extracts_named_imports() {
+        let source = indoc! {r#"
+            import React from "react";
+            import { UserFields, PostFields as Post } from './fragments';
+            import type { Viewer } from "../types";
+        "#};
+
+        assert_eq!(
+            extract_imports(source),
+            vec![
+                Import {
+                    local: "UserFields".to_string(),
+                    imported: "UserFields".to_string(),
+                    module: "./fragments".to_string(),
+                },
+                Import {
+                    local: "Post".to_string(),
+                    imported: "PostFields".to_string(),
+                    module: "./fragments".to_string(),
+                },
+                Import {
+                    local: "Viewer".to_string(),
+                    imported: "Viewer".to_string(),
+                    module: "../types".to_string(),
+                },
+            ]
+        );
+    }
+}
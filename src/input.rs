@@ -0,0 +1,574 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    fs,
+    path::{Component, Path, PathBuf},
+    process,
+};
+
+use graphql_normalize::NormalizeError;
+use graphql_parser::{parse_query, query};
+
+use crate::extract::{extract_imports, extract_templates, Template};
+
+/// Extensions of the files holding plain GraphQL documents.
+const QUERY_EXTENSIONS: &[&str] = &["graphql", "gql"];
+
+/// Extensions of the TypeScript and JavaScript files GraphQL templates are
+/// extracted from.
+const SOURCE_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs"];
+
+/// Expands each argument, which may be a file, a directory or a glob pattern,
+/// into the query files it names. Directories are searched recursively, and
+/// only files with a query or source extension are taken from directories and
+/// patterns. A directory or pattern naming no such file is an error.
+pub fn expand_query_paths(args: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut paths = Vec::new();
+
+    for arg in args {
+        let path = Path::new(arg);
+        if path.is_file() {
+            paths.push(path.to_path_buf());
+            continue;
+        }
+
+        let found = paths.len();
+        if path.is_dir() {
+            collect_dir(path, &mut paths);
+        } else {
+            let matches =
+                glob::glob(arg).map_err(|err| format!("invalid query pattern {arg}: {err}"))?;
+            for path in matches.filter_map(Result::ok) {
+                if path.is_dir() {
+                    collect_dir(&path, &mut paths);
+                } else if is_query_file(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+        if paths.len() == found {
+            return Err(format!("no query files found in {arg}"));
+        }
+    }
+
+    let mut unique = Vec::with_capacity(paths.len());
+    for path in paths {
+        if !unique.contains(&path) {
+            unique.push(path);
+        }
+    }
+    Ok(unique)
+}
+
+fn collect_dir(dir: &Path, paths: &mut Vec<PathBuf>) {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .expect("Failed to read query directory")
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .collect();
+    entries.sort();
+
+    for entry in entries {
+        if entry.is_dir() {
+            collect_dir(&entry, paths);
+        } else if is_query_file(&entry) {
+            paths.push(entry);
+        }
+    }
+}
+
+fn is_query_file(path: &Path) -> bool {
+    has_extension(path, QUERY_EXTENSIONS) || has_extension(path, SOURCE_EXTENSIONS)
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .is_some_and(|ext| extensions.iter().any(|e| ext == *e))
+}
+
+/// Whether GraphQL documents are extracted from the file rather than read
+/// from it directly.
+pub fn is_source_file(path: &Path) -> bool {
+    has_extension(path, SOURCE_EXTENSIONS)
+}
+
+/// A position in one of the files a [`QuerySource`] was read from.
+pub struct Location<'s> {
+    pub path: &'s Path,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Display for Location<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.path.display(), self.line, self.column)
+    }
+}
+
+/// A document read from a file or extracted from a template, and where its
+/// text starts in that file.
+struct Segment {
+    path: PathBuf,
+    line: usize,
+    column: usize,
+    /// The line of the combined text the segment starts on.
+    start: usize,
+    text: String,
+}
+
+impl Segment {
+    /// Maps a one-based position within the segment's text to its file.
+    fn origin(&self, line: usize, column: usize) -> (usize, usize) {
+        if line <= 1 {
+            (self.line, self.column + column - 1)
+        } else {
+            (self.line + line - 1, column)
+        }
+    }
+}
+
+/// Query text gathered from any number of GraphQL, TypeScript and
+/// JavaScript files, combined into one document so that fragments defined in
+/// one file resolve when another spreads them. Positions in the combined text
+/// map back to the file, line and column they came from.
+#[derive(Default)]
+pub struct QuerySource {
+    text: String,
+    segments: Vec<Segment>,
+    files: HashMap<PathBuf, String>,
+    templates: HashMap<PathBuf, Vec<Template>>,
+    seen: HashSet<(PathBuf, usize, usize)>,
+}
+
+impl QuerySource {
+    /// Reads every file named by `args`, which may be files, directories or
+    /// glob patterns. GraphQL templates are extracted from TypeScript and
+    /// JavaScript files along with the templates interpolated into them.
+    pub fn load(args: &[String]) -> Self {
+        let mut source = QuerySource::default();
+
+        let paths = expand_query_paths(args).unwrap_or_else(|err| {
+            eprintln!("{err}");
+            process::exit(1);
+        });
+        for path in paths {
+            if is_source_file(&path) {
+                for template in source.templates(&path) {
+                    source.push_template(&path, &template);
+                }
+            } else {
+                let text = source.read(&path).to_string();
+                source.push(&path, 1, 1, text);
+            }
+        }
+
+        source
+    }
+
+    /// The combined text of every document.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Parses the combined text, exiting with a diagnostic on error.
+    pub fn parse(&self) -> query::Document<'static, String> {
+        parse_query::<String>(&self.text)
+            .map(|doc| doc.into_static())
+            .unwrap_or_else(|err| self.exit_with_diagnostic(&err.into()))
+    }
+
+    /// Maps a one-based position in the combined text to its file.
+    pub fn locate(&self, line: usize, column: usize) -> Option<Location<'_>> {
+        let segment = self
+            .segments
+            .iter()
+            .rev()
+            .find(|segment| segment.start <= line)?;
+        let (line, column) = segment.origin(line - segment.start + 1, column);
+        Some(Location {
+            path: &segment.path,
+            line,
+            column,
+        })
+    }
+
+    /// Prints the error as a code frame into the file it points at and exits
+    /// unsuccessfully.
+    pub fn exit_with_diagnostic(&self, err: &NormalizeError) -> ! {
+        eprintln!("{}", self.render(err));
+        process::exit(1);
+    }
+
+    /// Renders the error as a code frame into the file it points at, preceded
+    /// by the file's path.
+    fn render(&self, err: &NormalizeError) -> String {
+        let located = err
+            .position()
+            .and_then(|(line, column)| self.locate(line, column));
+        match located {
+            Some(location) => {
+                let relocated = relocate(err, location.line, location.column);
+                format!(
+                    "{}:\n{}",
+                    location.path.display(),
+                    relocated.render(&self.files[location.path])
+                )
+            }
+            None => err.render(&self.text),
+        }
+    }
+
+    /// Prints every document with the position it was found at, formatted.
+    pub fn print_documents(&self) {
+        for segment in &self.segments {
+            let doc = parse_query::<String>(&segment.text).unwrap_or_else(|err| {
+                let err = NormalizeError::from(err);
+                let (line, column) = err.position().unwrap_or((1, 1));
+                let (line, column) = segment.origin(line, column);
+                eprintln!("{}:", segment.path.display());
+                eprintln!(
+                    "{}",
+                    relocate(&err, line, column).render(&self.files[&segment.path])
+                );
+                process::exit(1);
+            });
+            println!(
+                "# {}:{}:{}\n{doc}",
+                segment.path.display(),
+                segment.line,
+                segment.column
+            );
+        }
+    }
+
+    fn read(&mut self, path: &Path) -> &str {
+        self.files.entry(path.to_path_buf()).or_insert_with(|| {
+            fs::read_to_string(path).unwrap_or_else(|err| {
+                eprintln!("Failed to read {}: {err}", path.display());
+                process::exit(1);
+            })
+        })
+    }
+
+    fn templates(&mut self, path: &Path) -> Vec<Template> {
+        if let Some(templates) = self.templates.get(path) {
+            return templates.clone();
+        }
+        let templates = extract_templates(self.read(path));
+        self.templates.insert(path.to_path_buf(), templates.clone());
+        templates
+    }
+
+    /// Appends a document unless it was already added, returning whether it
+    /// was.
+    fn push(&mut self, path: &Path, line: usize, column: usize, text: String) -> bool {
+        if !self.seen.insert((path.to_path_buf(), line, column)) {
+            return false;
+        }
+        let segment = Segment {
+            path: path.to_path_buf(),
+            line,
+            column,
+            start: self.text.lines().count() + 1,
+            text,
+        };
+        self.text.push_str(&segment.text);
+        if !segment.text.ends_with('\n') {
+            self.text.push('\n');
+        }
+        self.segments.push(segment);
+        true
+    }
+
+    /// Adds a template followed by the templates interpolated into it,
+    /// resolving them within the same file or through its relative imports.
+    fn push_template(&mut self, path: &Path, template: &Template) {
+        if !self.push(path, template.line, template.column, template.text.clone()) {
+            return;
+        }
+
+        for name in &template.interpolations {
+            if let Some((dep_path, dep)) = self.resolve(path, name) {
+                self.push_template(&dep_path, &dep);
+            }
+        }
+    }
+
+    fn resolve(&mut self, path: &Path, name: &str) -> Option<(PathBuf, Template)> {
+        let local = self
+            .templates(path)
+            .into_iter()
+            .find(|t| t.name.as_deref() == Some(name));
+        if let Some(template) = local {
+            return Some((path.to_path_buf(), template));
+        }
+
+        let import = extract_imports(self.read(path))
+            .into_iter()
+            .find(|import| import.local == name)?;
+        let target = resolve_module(path, &import.module)?;
+        let template = self
+            .templates(&target)
+            .into_iter()
+            .find(|t| t.name.as_deref() == Some(import.imported.as_str()))?;
+        Some((target, template))
+    }
+}
+
+/// Resolves a relative module specifier the way bundlers do, trying each
+/// source extension and `index` files.
+fn resolve_module(from: &Path, module: &str) -> Option<PathBuf> {
+    if !module.starts_with('.') {
+        return None;
+    }
+    let mut base = from.parent().unwrap_or(Path::new("")).to_path_buf();
+    for component in Path::new(module).components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => {
+                base.pop();
+            }
+            component => base.push(component),
+        }
+    }
+    if base.is_file() {
+        return Some(base);
+    }
+    let with_extension =
+        |path: &Path, ext: &str| PathBuf::from(format!("{}.{ext}", path.display()));
+    SOURCE_EXTENSIONS
+        .iter()
+        .map(|ext| with_extension(&base, ext))
+        .chain(
+            SOURCE_EXTENSIONS
+                .iter()
+                .map(|ext| with_extension(&base.join("index"), ext)),
+        )
+        .find(|candidate| candidate.is_file())
+}
+
+fn relocate(err: &NormalizeError, line: usize, column: usize) -> NormalizeError {
+    match err.clone() {
+        NormalizeError::Parse { message, .. } => NormalizeError::Parse {
+            message,
+            position: Some((line, column)),
+        },
+        NormalizeError::FragmentCycle { path, .. } => {
+            NormalizeError::FragmentCycle { path, line, column }
+        }
+        err => err,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand_query_paths, resolve_module, QuerySource, Segment};
+    use graphql_normalize::NormalizeError;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+    use std::{fs, path::Path};
+
+    fn write(dir: &Path, name: &str, contents: &str) {
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn arg(path: &Path) -> String {
+        path.display().to_string()
+    }
+
+    #[test]
+    fn expands_directories_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "b.graphql", "{ b }");
+        write(dir.path(), "a/user.gql", "{ a }");
+        write(dir.path(), "a/query.ts", "");
+        write(dir.path(), "README.md", "");
+        write(dir.path(), "schema.json", "{}");
+
+        assert_eq!(
+            expand_query_paths(&[arg(dir.path())]).unwrap(),
+            vec![
+                dir.path().join("a/query.ts"),
+                dir.path().join("a/user.gql"),
+                dir.path().join("b.graphql"),
+            ]
+        );
+    }
+
+    #[test]
+    fn expands_globs_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "c.graphql", "{ c }");
+        write(dir.path(), "a.graphql", "{ a }");
+        write(dir.path(), "b.txt", "");
+        write(dir.path(), "nested/d.graphql", "{ d }");
+
+        assert_eq!(
+            expand_query_paths(&[arg(&dir.path().join("*"))]).unwrap(),
+            vec![
+                dir.path().join("a.graphql"),
+                dir.path().join("c.graphql"),
+                dir.path().join("nested/d.graphql"),
+            ]
+        );
+        assert_eq!(
+            expand_query_paths(&[
+                arg(&dir.path().join("c.graphql")),
+                arg(&dir.path().join("*.graphql")),
+            ])
+            .unwrap(),
+            vec![dir.path().join("c.graphql"), dir.path().join("a.graphql")]
+        );
+    }
+
+    #[test]
+    fn rejects_paths_without_query_files() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "README.md", "");
+
+        let empty = arg(dir.path());
+        assert_eq!(
+            expand_query_paths(std::slice::from_ref(&empty)),
+            Err(format!("no query files found in {empty}"))
+        );
+
+        let pattern = arg(&dir.path().join("*.graphql"));
+        assert_eq!(
+            expand_query_paths(std::slice::from_ref(&pattern)),
+            Err(format!("no query files found in {pattern}"))
+        );
+    }
+
+    #[test]
+    fn maps_segment_positions_to_its_file() {
+        let segment = Segment {
+            path: "query.ts".into(),
+            line: 4,
+            column: 28,
+            start: 1,
+            text: String::new(),
+        };
+
+        assert_eq!(segment.origin(1, 5), (4, 32));
+        assert_eq!(segment.origin(3, 7), (6, 7));
+    }
+
+    #[test]
+    fn resolves_relative_modules() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "app/query.ts", "");
+        write(dir.path(), "app/fragments.ts", "");
+        write(dir.path(), "app/shared/index.tsx", "");
+        write(dir.path(), "lib/user.js", "");
+        let from = dir.path().join("app/query.ts");
+
+        assert_eq!(
+            resolve_module(&from, "./fragments"),
+            Some(dir.path().join("app/fragments.ts"))
+        );
+        assert_eq!(
+            resolve_module(&from, "./fragments.ts"),
+            Some(dir.path().join("app/fragments.ts"))
+        );
+        assert_eq!(
+            resolve_module(&from, "./shared"),
+            Some(dir.path().join("app/shared/index.tsx"))
+        );
+        assert_eq!(
+            resolve_module(&from, "../lib/user"),
+            Some(dir.path().join("lib/user.js"))
+        );
+        assert_eq!(resolve_module(&from, "./missing"), None);
+        assert_eq!(resolve_module(&from, "@apollo/client"), None);
+    }
+
+    /// A file with two queries, both interpolating a fragment imported from
+    /// another file.
+    fn write_templates(dir: &Path, fragment: &str) {
+        write(
+            dir,
+            "fragments.ts",
+            &format!("import {{ gql }} from \"@apollo/client\";\n\nexport const UserFields = gql`{fragment}`;\n"),
+        );
+        write(
+            dir,
+            "queries.ts",
+            indoc! {r#"
+                import { gql } from "@apollo/client";
+                import { UserFields } from "./fragments";
+
+                export const GetUser = gql`
+                  query GetUser {
+                    user {
+                      ...UserFields
+                    }
+                  }
+                  ${UserFields}
+                `;
+
+                export const GetViewer = gql`
+                  query GetViewer {
+                    viewer {
+                      ...UserFields
+                    }
+                  }
+                  ${UserFields}
+                `;
+            "#},
+        );
+    }
+
+    #[test]
+    fn loads_imported_interpolations_once() {
+        let dir = tempfile::tempdir().unwrap();
+        write_templates(
+            dir.path(),
+            "\n  fragment UserFields on User {\n    id\n  }\n",
+        );
+        let source = QuerySource::load(&[arg(&dir.path().join("queries.ts"))]);
+        let locate = |line, column| {
+            let location = source.locate(line, column).unwrap();
+            let path = location.path.strip_prefix(dir.path()).unwrap();
+            (path.display().to_string(), location.line, location.column)
+        };
+
+        let definitions: Vec<String> = source
+            .parse()
+            .definitions
+            .iter()
+            .map(|d| d.to_string().lines().next().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            definitions,
+            vec![
+                "query GetUser {",
+                "fragment UserFields on User {",
+                "query GetViewer {",
+            ]
+        );
+        assert_eq!(locate(2, 3), (String::from("queries.ts"), 5, 3));
+        assert_eq!(locate(9, 3), (String::from("fragments.ts"), 4, 3));
+        assert_eq!(locate(13, 3), (String::from("queries.ts"), 14, 3));
+    }
+
+    #[test]
+    fn renders_diagnostics_in_the_imported_file() {
+        let dir = tempfile::tempdir().unwrap();
+        write_templates(
+            dir.path(),
+            "\n  fragment UserFields on User {\n    id(\n  }\n",
+        );
+        let source = QuerySource::load(&[arg(&dir.path().join("queries.ts"))]);
+        let err =
+            NormalizeError::from(graphql_parser::parse_query::<String>(source.text()).unwrap_err());
+
+        assert_eq!(
+            source.render(&err),
+            format!(
+                "{}:\nerror: Unexpected `}}[Punctuator]`; Expected Name\n --> 6:3\n  |\n6 |   }}\n  |   ^",
+                dir.path().join("fragments.ts").display()
+            )
+        );
+    }
+}
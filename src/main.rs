@@ -1,14 +1,27 @@
-mod focus;
-mod prune;
-mod sort;
-mod util;
+mod extract;
+mod input;
 
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use clap_stdin::FileOrStdin;
-use graphql_normalize::normalize;
-use graphql_parser::parse_schema;
+use graphql_normalize::{hash, hash_operations, normalize_with, NormalizeError, NormalizeOptions};
+use graphql_parser::{parse_schema, schema};
+use graphql_printer::{print_schema, CommaStyle, DescriptionStyle, PrintOptions};
+use graphql_schema_utils::{
+    coverage, dependents, diff, focus, from_introspection, graph, prune, sort, to_introspection,
+    validate, Boundary, Comments, DependentsOptions, FocusOptions, GraphFormat, GraphOptions,
+    PruneOptions, SortOptions, SortOrder,
+};
+use input::{is_source_file, QuerySource};
+use serde::Deserialize;
+
+/// The config file read when `--config` is not given, if it exists.
+const CONFIG_FILE: &str = "graphql-document-utils.toml";
 
 #[derive(Parser, Debug)]
 #[clap(version)]
@@ -27,38 +40,369 @@ enum Commands {
 
 #[derive(Subcommand, Debug)]
 enum QueryCommands {
+    /// Print the query with its fields, arguments and definitions sorted
     Normalize {
         #[clap(short, default_value = "-")]
         path: FileOrStdin,
         #[clap(short, long, default_value_t = false)]
         minify: bool,
+        /// Replace literal values with zero values and drop aliases, so
+        /// operations only differing by inlined values print the same
+        #[clap(long, default_value_t = false)]
+        signature: bool,
+        /// Replace fragment spreads with the fragments' selections
+        #[clap(long, default_value_t = false)]
+        inline_fragments: bool,
+        /// Rename variables to $v0, $v1, ... and print the mapping to stderr
+        #[clap(long, default_value_t = false)]
+        rename_variables: bool,
+        #[command(flatten)]
+        print: PrintArgs,
+    },
+    /// Print the GraphQL documents embedded in TypeScript and JavaScript files
+    Extract {
+        /// Source files, directories or glob patterns
+        #[arg(num_args = 1.., required = true)]
+        paths: Vec<String>,
+    },
+    Hash {
+        #[clap(short, default_value = "-")]
+        path: FileOrStdin,
+        /// Print a separate hash for every operation in the document
+        #[clap(short, long, default_value_t = false)]
+        operations: bool,
+    },
+    /// Check the query against the schema and report every validation error
+    Validate {
+        #[arg(short, long)]
+        schema: PathBuf,
+        /// Format of the schema file, detected from its extension by default
+        #[arg(long, value_enum, default_value_t)]
+        schema_format: SchemaFormat,
+
+        /// Query files, directories or glob patterns
+        #[arg(short, long, num_args = 1.., required = true)]
+        query: Vec<String>,
     },
 }
 
 #[derive(Subcommand, Debug)]
 enum SchemaCommands {
+    /// Print the schema with consistent formatting, keeping its comments
     Format {
         #[arg(short, long)]
         schema: PathBuf,
+        /// Format of the schema file, detected from its extension by default
+        #[arg(long, value_enum, default_value_t)]
+        schema_format: SchemaFormat,
+        /// Drop the `#` comments of the schema instead of keeping them
+        #[arg(long)]
+        strip_comments: bool,
+        #[command(flatten)]
+        print: PrintArgs,
     },
+    /// Print the given types and every type they reference
     Focus {
         #[arg(short, long)]
         schema: PathBuf,
+        /// Format of the schema file, detected from its extension by default
+        #[arg(long, value_enum, default_value_t)]
+        schema_format: SchemaFormat,
 
         #[arg(num_args = 1..)]
         types: Vec<String>,
+        /// Only follow this many references from the focused types
+        #[arg(long)]
+        depth: Option<usize>,
+        /// Replace the types past the maximum depth with scalars instead of
+        /// removing the fields that refer to them
+        #[arg(long, requires = "depth")]
+        stub: bool,
+        #[command(flatten)]
+        print: PrintArgs,
     },
-    Prune {
+    /// Print the types referencing the given types, and the fields leading to
+    /// them from the root types
+    Dependents {
         #[arg(short, long)]
         schema: PathBuf,
+        /// Format of the schema file, detected from its extension by default
+        #[arg(long, value_enum, default_value_t)]
+        schema_format: SchemaFormat,
 
+        #[arg(num_args = 1..)]
+        types: Vec<String>,
+        #[command(flatten)]
+        print: PrintArgs,
+    },
+    /// Print the type dependency graph as Graphviz DOT, Mermaid or JSON
+    Graph {
         #[arg(short, long)]
-        query: PathBuf,
+        schema: PathBuf,
+        /// Format of the schema file, detected from its extension by default
+        #[arg(long, value_enum, default_value_t)]
+        schema_format: SchemaFormat,
+
+        #[arg(long, value_enum, default_value_t)]
+        format: DiagramFormat,
+        /// Only include these types and the types they reference
+        #[arg(num_args = 0..)]
+        types: Vec<String>,
+    },
+    /// Print the schema without the types and fields no query uses
+    Prune {
+        #[arg(short, long)]
+        schema: PathBuf,
+        /// Format of the schema file, detected from its extension by default
+        #[arg(long, value_enum, default_value_t)]
+        schema_format: SchemaFormat,
+
+        /// Query files, directories or glob patterns; fields used by any of them are kept
+        #[arg(short, long, num_args = 1.., required = true)]
+        query: Vec<String>,
+        #[command(flatten)]
+        print: PrintArgs,
     },
+    /// Print the schema with its definitions sorted
     Sort {
         #[arg(short, long)]
         schema: PathBuf,
+        /// Format of the schema file, detected from its extension by default
+        #[arg(long, value_enum, default_value_t)]
+        schema_format: SchemaFormat,
+        /// Drop the `#` comments of the schema instead of keeping them
+        #[arg(long)]
+        strip_comments: bool,
+
+        /// How type definitions are ordered
+        #[arg(long, value_enum, default_value_t)]
+        order: TypeOrder,
+        /// Place type extensions right after the type they extend
+        #[arg(long)]
+        group_extensions: bool,
+        /// Sort inside definitions too, as all of the flags below do
+        #[arg(long)]
+        deep: bool,
+        /// Sort the fields of object, interface and input types
+        #[arg(long)]
+        fields: bool,
+        /// Sort the arguments of fields and directives
+        #[arg(long)]
+        arguments: bool,
+        /// Sort the values of enums
+        #[arg(long)]
+        enum_values: bool,
+        /// Sort the member types of unions
+        #[arg(long)]
+        union_members: bool,
+        /// Sort the interfaces types implement
+        #[arg(long)]
+        interfaces: bool,
+        #[command(flatten)]
+        print: PrintArgs,
     },
+    /// Compare two schemas and classify each change as breaking, dangerous or safe
+    Diff {
+        #[arg(long)]
+        old: PathBuf,
+
+        #[arg(long)]
+        new: PathBuf,
+        /// Format of the schema file, detected from its extension by default
+        #[arg(long, value_enum, default_value_t)]
+        schema_format: SchemaFormat,
+    },
+    /// Count the operations using each type and field of the schema
+    Coverage {
+        #[arg(short, long)]
+        schema: PathBuf,
+        /// Format of the schema file, detected from its extension by default
+        #[arg(long, value_enum, default_value_t)]
+        schema_format: SchemaFormat,
+
+        /// Query files, directories or glob patterns
+        #[arg(short, long, num_args = 1.., required = true)]
+        query: Vec<String>,
+
+        #[arg(long, value_enum, default_value_t)]
+        format: CoverageFormat,
+        /// Only list the types and fields no operation uses
+        #[arg(long, default_value_t = false)]
+        unused: bool,
+    },
+    /// Print the schema as an `IntrospectionQuery` JSON result
+    Introspect {
+        #[arg(short, long)]
+        schema: PathBuf,
+        /// Format of the schema file, detected from its extension by default
+        #[arg(long, value_enum, default_value_t)]
+        schema_format: SchemaFormat,
+    },
+}
+
+/// How a `--schema` file is parsed.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum SchemaFormat {
+    /// Introspection JSON for `.json` files, SDL otherwise
+    #[default]
+    Auto,
+    Sdl,
+    Introspection,
+}
+
+/// How `schema coverage` prints its report.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum CoverageFormat {
+    #[default]
+    Table,
+    Json,
+}
+
+/// How `schema graph` prints the type graph.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum DiagramFormat {
+    #[default]
+    Dot,
+    Mermaid,
+    Json,
+}
+
+/// How `schema sort` orders type definitions.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum TypeOrder {
+    /// By name
+    #[default]
+    Alphabetical,
+    /// Scalars, enums, inputs, interfaces, objects and then unions
+    Kind,
+    /// Query, Mutation and Subscription first
+    RootsFirst,
+    /// Root types first, then each type after the first type referencing it
+    Dependency,
+}
+
+// How documents are printed. The flags override the `[print]` table of the
+// config file.
+#[derive(clap::Args, Debug)]
+#[command(next_help_heading = "Printing")]
+struct PrintArgs {
+    /// TOML file with a `[print]` table of printing options, by default
+    /// `graphql-document-utils.toml` in the current directory
+    #[arg(long)]
+    config: Option<PathBuf>,
+    /// Spaces per indentation level, or the width of a tab with `--tabs`
+    #[arg(long)]
+    indent: Option<usize>,
+    /// Indent with tabs instead of spaces
+    #[arg(long, overrides_with = "no_tabs")]
+    tabs: bool,
+    /// Indent with spaces
+    #[arg(long, overrides_with = "tabs")]
+    no_tabs: bool,
+    /// Put arguments on lines of their own when a line would be wider than this
+    #[arg(long)]
+    max_width: Option<usize>,
+    /// How descriptions are quoted
+    #[arg(long, value_enum)]
+    descriptions: Option<DescriptionQuoting>,
+    /// Where commas separate arguments and the items of lists and objects
+    #[arg(long, value_enum)]
+    commas: Option<Commas>,
+    /// End the output with a newline
+    #[arg(long, overrides_with = "no_trailing_newline")]
+    trailing_newline: bool,
+    /// Leave out the newline at the end of the output
+    #[arg(long, overrides_with = "trailing_newline")]
+    no_trailing_newline: bool,
+}
+
+/// How descriptions are printed.
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+enum DescriptionQuoting {
+    /// Block strings for descriptions spanning several lines
+    Auto,
+    /// Always single-line strings
+    SingleLine,
+    /// Always block strings on their own lines
+    Block,
+}
+
+/// Where commas are printed.
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+enum Commas {
+    /// Between items on the same line
+    Inline,
+    /// Also at the end of the lines of wrapped arguments
+    Always,
+    /// Never, separating items with spaces
+    Never,
+}
+
+/// The contents of a config file.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    print: PrintConfig,
+}
+
+/// The `[print]` table of a config file, named like the printing flags.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+struct PrintConfig {
+    indent: Option<usize>,
+    tabs: Option<bool>,
+    max_width: Option<usize>,
+    descriptions: Option<DescriptionQuoting>,
+    commas: Option<Commas>,
+    trailing_newline: Option<bool>,
+}
+
+impl PrintArgs {
+    /// Combines the flags with the config file, falling back to the defaults
+    /// for options set by neither.
+    fn options(&self) -> PrintOptions {
+        self.merge(&load_config(self.config.as_deref()))
+    }
+
+    /// Combines the flags with the `[print]` table of a config file, the flags
+    /// taking precedence.
+    fn merge(&self, config: &PrintConfig) -> PrintOptions {
+        let defaults = PrintOptions::default();
+
+        PrintOptions {
+            indent: self.indent.or(config.indent).unwrap_or(defaults.indent),
+            tabs: flag(self.tabs, self.no_tabs)
+                .or(config.tabs)
+                .unwrap_or(defaults.tabs),
+            max_width: self.max_width.or(config.max_width),
+            descriptions: match self.descriptions.or(config.descriptions) {
+                None => defaults.descriptions,
+                Some(DescriptionQuoting::Auto) => DescriptionStyle::Auto,
+                Some(DescriptionQuoting::SingleLine) => DescriptionStyle::SingleLine,
+                Some(DescriptionQuoting::Block) => DescriptionStyle::Block,
+            },
+            trailing_newline: flag(self.trailing_newline, self.no_trailing_newline)
+                .or(config.trailing_newline)
+                .unwrap_or(defaults.trailing_newline),
+            commas: match self.commas.or(config.commas) {
+                None => defaults.commas,
+                Some(Commas::Inline) => CommaStyle::Inline,
+                Some(Commas::Always) => CommaStyle::Always,
+                Some(Commas::Never) => CommaStyle::Never,
+            },
+        }
+    }
+}
+
+/// The value of a `--flag`/`--no-flag` pair, if either was given.
+fn flag(yes: bool, no: bool) -> Option<bool> {
+    match (yes, no) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    }
 }
 
 fn main() {
@@ -66,47 +410,398 @@ fn main() {
 
     match args.cmd {
         Commands::Query(query_commands) => match query_commands {
-            QueryCommands::Normalize { path, minify } => {
-                let query_content: String = path.contents().expect("Unable to read input");
-                let normalized = normalize(&query_content).expect("Could not normalize");
+            QueryCommands::Normalize {
+                path,
+                minify,
+                signature,
+                inline_fragments,
+                rename_variables,
+                print,
+            } => {
+                let (query_content, source) = read_query(path);
+                let options = NormalizeOptions {
+                    signature,
+                    inline_fragments,
+                    rename_variables,
+                    print: print.options(),
+                };
+                let normalized = normalize_with(&query_content, &options).unwrap_or_else(|err| {
+                    exit_with_query_diagnostic(&err, &query_content, source.as_ref())
+                });
+
+                for renamed in &normalized.variables {
+                    eprintln!(
+                        "# {}",
+                        renamed.operation.as_deref().unwrap_or("(anonymous)")
+                    );
+                    for (old, new) in &renamed.variables {
+                        eprintln!("${old} -> ${new}");
+                    }
+                }
+                let normalized = normalized.document;
 
                 if minify {
-                    let minified =
-                        graphql_parser::minify_query(normalized).expect("Could not minify");
+                    let minified = graphql_parser::minify_query(normalized).unwrap_or_else(|err| {
+                        let err = NormalizeError::Minify(err.to_string());
+                        exit_with_query_diagnostic(&err, &query_content, source.as_ref())
+                    });
 
                     println!("{minified}");
                 } else {
-                    println!("{normalized}");
+                    print!("{normalized}");
+                }
+            }
+            QueryCommands::Extract { paths } => {
+                QuerySource::load(&paths).print_documents();
+            }
+            QueryCommands::Hash { path, operations } => {
+                let (query_content, source) = read_query(path);
+
+                if operations {
+                    let hashes = hash_operations(&query_content).unwrap_or_else(|err| {
+                        exit_with_query_diagnostic(&err, &query_content, source.as_ref())
+                    });
+                    for op in hashes {
+                        println!("{}  {}", op.hash, op.name.as_deref().unwrap_or("-"));
+                    }
+                } else {
+                    let hash = hash(&query_content).unwrap_or_else(|err| {
+                        exit_with_query_diagnostic(&err, &query_content, source.as_ref())
+                    });
+                    println!("{hash}");
+                }
+            }
+            QueryCommands::Validate {
+                schema,
+                schema_format,
+                query,
+            } => {
+                let schema_doc = load_schema(&schema, schema_format);
+                let source = QuerySource::load(&query);
+                let query_doc = source.parse();
+
+                let errors = validate(&schema_doc, &query_doc);
+                for error in &errors {
+                    match error
+                        .locations
+                        .first()
+                        .and_then(|pos| source.locate(pos.line, pos.column))
+                    {
+                        Some(location) => println!("{location}: {}", error.message),
+                        None => println!("{}", error.message),
+                    }
+                }
+                if !errors.is_empty() {
+                    process::exit(1);
                 }
             }
         },
         Commands::Schema(schema_commands) => match schema_commands {
-            SchemaCommands::Format { schema } => {
-                let schema_str = fs::read_to_string(schema).expect("Failed to read schema file");
-                let schema_doc =
-                    parse_schema::<String>(&schema_str).expect("Failed to parse schema");
-                println!("{schema_doc}");
+            SchemaCommands::Format {
+                schema,
+                schema_format,
+                strip_comments,
+                print,
+            } => {
+                let schema_doc = load_schema(&schema, schema_format);
+                let comments = if strip_comments {
+                    Comments::default()
+                } else {
+                    load_comments(&schema, schema_format, &schema_doc)
+                };
+                print!("{}", comments.print(&schema_doc, &print.options()));
+            }
+            SchemaCommands::Focus {
+                schema,
+                schema_format,
+                types,
+                depth,
+                stub,
+                print,
+            } => {
+                let schema_doc = load_schema(&schema, schema_format);
+                let boundary = if stub { Boundary::Stub } else { Boundary::Trim };
+                let focused = focus(
+                    &schema_doc,
+                    &FocusOptions {
+                        types,
+                        depth,
+                        boundary,
+                    },
+                );
+
+                print!("{}", print_schema(&focused, &print.options()));
+            }
+            SchemaCommands::Dependents {
+                schema,
+                schema_format,
+                types,
+                print,
+            } => {
+                let schema_doc = load_schema(&schema, schema_format);
+                let dependents = dependents(&schema_doc, &DependentsOptions { types });
+
+                print!("{}", dependents.print(&print.options()));
             }
-            SchemaCommands::Focus { schema, types } => {
-                let schema_str = fs::read_to_string(&schema).expect("Failed to read schema file");
-                let types: Vec<&str> = types.iter().map(|s| s.as_str()).collect();
-                let focused = focus::process(&schema_str, &types);
+            SchemaCommands::Graph {
+                schema,
+                schema_format,
+                format,
+                types,
+            } => {
+                let schema_doc = load_schema(&schema, schema_format);
+                let format = match format {
+                    DiagramFormat::Dot => GraphFormat::Dot,
+                    DiagramFormat::Mermaid => GraphFormat::Mermaid,
+                    DiagramFormat::Json => GraphFormat::Json,
+                };
 
-                println!("{focused}");
+                print!("{}", graph(&schema_doc, &GraphOptions { types, format }));
             }
-            SchemaCommands::Prune { schema, query } => {
-                let schema_str = fs::read_to_string(schema).expect("Failed to read schema file");
-                let query_str = fs::read_to_string(query).expect("Failed to read query file");
-                let pruned = prune::process(&schema_str, &query_str);
+            SchemaCommands::Prune {
+                schema,
+                schema_format,
+                query,
+                print,
+            } => {
+                let schema_doc = load_schema(&schema, schema_format);
+                let query_doc = QuerySource::load(&query).parse();
+                let pruned = prune(&schema_doc, &query_doc, &PruneOptions::default())
+                    .unwrap_or_else(|err| {
+                        eprintln!("{err}");
+                        process::exit(1);
+                    });
 
-                println!("{pruned}");
+                print!("{}", print_schema(&pruned, &print.options()));
             }
-            SchemaCommands::Sort { schema } => {
-                let schema_str = fs::read_to_string(schema).expect("Failed to read schema file");
-                let sorted = sort::process(&schema_str);
+            SchemaCommands::Sort {
+                schema,
+                schema_format,
+                strip_comments,
+                order,
+                group_extensions,
+                deep,
+                fields,
+                arguments,
+                enum_values,
+                union_members,
+                interfaces,
+                print,
+            } => {
+                let schema_doc = load_schema(&schema, schema_format);
+                let options = SortOptions {
+                    order: match order {
+                        TypeOrder::Alphabetical => SortOrder::Alphabetical,
+                        TypeOrder::Kind => SortOrder::Kind,
+                        TypeOrder::RootsFirst => SortOrder::RootsFirst,
+                        TypeOrder::Dependency => SortOrder::Dependency,
+                    },
+                    group_extensions,
+                    fields: deep || fields,
+                    arguments: deep || arguments,
+                    enum_values: deep || enum_values,
+                    union_members: deep || union_members,
+                    interfaces: deep || interfaces,
+                };
+                let sorted = sort(&schema_doc, &options);
+                let comments = if strip_comments {
+                    Comments::default()
+                } else {
+                    load_comments(&schema, schema_format, &schema_doc)
+                };
 
-                println!("{sorted}");
+                print!("{}", comments.print(&sorted, &print.options()));
+            }
+            SchemaCommands::Diff {
+                old,
+                new,
+                schema_format,
+            } => {
+                let old_doc = load_schema(&old, schema_format);
+                let new_doc = load_schema(&new, schema_format);
+
+                for change in diff(&old_doc, &new_doc) {
+                    println!("{change}");
+                }
+            }
+            SchemaCommands::Coverage {
+                schema,
+                schema_format,
+                query,
+                format,
+                unused,
+            } => {
+                let schema_doc = load_schema(&schema, schema_format);
+                let query_doc = QuerySource::load(&query).parse();
+                let mut report = coverage(&schema_doc, &query_doc);
+                if unused {
+                    report = report.unused();
+                }
+
+                match format {
+                    CoverageFormat::Table => print!("{report}"),
+                    CoverageFormat::Json => println!(
+                        "{}",
+                        serde_json::to_string_pretty(&report)
+                            .expect("Failed to serialize coverage report")
+                    ),
+                }
+            }
+            SchemaCommands::Introspect {
+                schema,
+                schema_format,
+            } => {
+                let schema_doc = load_schema(&schema, schema_format);
+                let introspection = serde_json::to_string_pretty(&to_introspection(&schema_doc))
+                    .expect("Failed to serialize introspection result");
+
+                println!("{introspection}");
             }
         },
     }
 }
+
+/// Reads and parses a schema file, either as SDL or as an introspection result.
+fn load_schema(path: &Path, format: SchemaFormat) -> schema::Document<'static, String> {
+    let schema_str = fs::read_to_string(path).expect("Failed to read schema file");
+
+    if is_introspection(path, format) {
+        from_introspection(&schema_str).expect("Failed to convert introspection result")
+    } else {
+        parse_schema::<String>(&schema_str)
+            .expect("Failed to parse schema")
+            .into_static()
+    }
+}
+
+fn is_introspection(path: &Path, format: SchemaFormat) -> bool {
+    match format {
+        SchemaFormat::Auto => path.extension().is_some_and(|ext| ext == "json"),
+        SchemaFormat::Sdl => false,
+        SchemaFormat::Introspection => true,
+    }
+}
+
+/// Collects the comments of an SDL schema file that `schema_doc` was loaded
+/// from. Introspection results have none.
+fn load_comments(
+    path: &Path,
+    format: SchemaFormat,
+    schema_doc: &schema::Document<'_, String>,
+) -> Comments {
+    if is_introspection(path, format) {
+        return Comments::default();
+    }
+    let schema_str = fs::read_to_string(path).expect("Failed to read schema file");
+    Comments::extract(&schema_str, schema_doc)
+}
+
+/// Reads the `[print]` table of the config file at `path`, or of the default
+/// config file when it exists.
+fn load_config(path: Option<&Path>) -> PrintConfig {
+    let path = match path {
+        Some(path) => path,
+        None if Path::new(CONFIG_FILE).is_file() => Path::new(CONFIG_FILE),
+        None => return PrintConfig::default(),
+    };
+    let config_str = fs::read_to_string(path).unwrap_or_else(|err| {
+        eprintln!("Failed to read {}: {err}", path.display());
+        process::exit(1);
+    });
+    let config: Config = toml::from_str(&config_str).unwrap_or_else(|err| {
+        eprintln!("Invalid config file {}: {err}", path.display());
+        process::exit(1);
+    });
+    config.print
+}
+
+/// Reads a query from a file or stdin. Documents are extracted from
+/// TypeScript and JavaScript files, in which case their source is returned to
+/// map errors back into the file.
+fn read_query(path: FileOrStdin) -> (String, Option<QuerySource>) {
+    if path.is_file() && is_source_file(Path::new(path.filename())) {
+        let source = QuerySource::load(&[path.filename().to_string()]);
+        (source.text().to_string(), Some(source))
+    } else {
+        (path.contents().expect("Unable to read input"), None)
+    }
+}
+
+/// Prints the error as a code frame into `source` and exits unsuccessfully.
+fn exit_with_diagnostic(err: &NormalizeError, source: &str) -> ! {
+    eprintln!("{}", err.render(source));
+    process::exit(1);
+}
+
+/// Like [`exit_with_diagnostic`], pointing into the original file when the
+/// query was extracted from one.
+fn exit_with_query_diagnostic(
+    err: &NormalizeError,
+    query_content: &str,
+    source: Option<&QuerySource>,
+) -> ! {
+    match source {
+        Some(source) => source.exit_with_diagnostic(err),
+        None => exit_with_diagnostic(err, query_content),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Config, PrintArgs};
+    use clap::Parser;
+    use graphql_printer::PrintOptions;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        print: PrintArgs,
+    }
+
+    fn merge(flags: &[&str], config: &str) -> PrintOptions {
+        let cli =
+            Cli::try_parse_from(std::iter::once("test").chain(flags.iter().copied())).unwrap();
+        let config: Config = toml::from_str(config).unwrap();
+        cli.print.merge(&config.print)
+    }
+
+    #[test]
+    fn flags_override_config() {
+        let options = merge(
+            &["--no-tabs", "--indent", "4"],
+            "[print]\ntabs = true\nindent = 8\n",
+        );
+
+        assert!(!options.tabs);
+        assert_eq!(options.indent, 4);
+
+        let options = merge(
+            &["--tabs", "--no-tabs", "--trailing-newline"],
+            "[print]\ntrailing-newline = false\n",
+        );
+
+        assert!(!options.tabs);
+        assert!(options.trailing_newline);
+    }
+
+    #[test]
+    fn unset_flags_fall_back_to_config() {
+        let options = merge(
+            &[],
+            "[print]\ntabs = true\nindent = 1\ntrailing-newline = false\n",
+        );
+
+        assert!(options.tabs);
+        assert_eq!(options.indent, 1);
+        assert!(!options.trailing_newline);
+        assert_eq!(options.max_width, PrintOptions::default().max_width);
+
+        let options = merge(&[], "");
+        let defaults = PrintOptions::default();
+
+        assert_eq!(options.tabs, defaults.tabs);
+        assert_eq!(options.indent, defaults.indent);
+        assert_eq!(options.trailing_newline, defaults.trailing_newline);
+    }
+}